@@ -1,8 +1,11 @@
 use proc_macro::TokenStream;
+use std::collections::BTreeMap;
+
 use quote::{ToTokens, format_ident, quote};
 use syn::{
-    Attribute, Error, Expr, ExprClosure, Fields, Item, ItemEnum, ItemStruct, Lit, Result, Type,
-    parse::Parser, parse_macro_input, spanned::Spanned,
+    Attribute, Error, Expr, ExprClosure, Field, Fields, GenericArgument, Generics, Ident, Item,
+    ItemEnum, ItemStruct, Lit, LitStr, Pat, PathArguments, Result, Type, Variant, parse::Parser,
+    parse_macro_input, parse_quote, spanned::Spanned,
 };
 
 pub fn derive_syntax(input: TokenStream) -> TokenStream {
@@ -27,12 +30,24 @@ pub fn derive_syntax(input: TokenStream) -> TokenStream {
     }
 }
 
+/// Returns an identifier for a local variable or label of the generated code.
+///
+/// It resolves at the macro definition site, so it can't clash with fields or user expressions.
+fn local(name: &str) -> Ident {
+    Ident::new(name, proc_macro2::Span::mixed_site())
+}
+
 struct Syntax {
     ty_input: Type,
     map_err: Option<Expr>,
     keyword: Option<Lit>,
     token: Option<ExprClosure>,
     c: Option<Lit>,
+    memo: Option<Ident>,
+    precedence: Option<Ident>,
+    context: Option<LitStr>,
+    trivia: Option<Type>,
+    no_trivia: Option<Ident>,
 }
 
 impl Default for Syntax {
@@ -43,6 +58,11 @@ impl Default for Syntax {
             keyword: None,
             token: None,
             c: None,
+            memo: None,
+            precedence: None,
+            context: None,
+            trivia: None,
+            no_trivia: None,
         }
     }
 }
@@ -65,6 +85,11 @@ fn parse_syntax_options(attrs: &[Attribute]) -> Result<Syntax> {
     let mut keyword: Option<Lit> = None;
     let mut c: Option<Lit> = None;
     let mut token: Option<ExprClosure> = None;
+    let mut memo: Option<Ident> = None;
+    let mut precedence: Option<Ident> = None;
+    let mut context: Option<LitStr> = None;
+    let mut trivia: Option<Type> = None;
+    let mut no_trivia: Option<Ident> = None;
 
     let parser = syn::meta::parser(|meta| {
         macro_rules! error {
@@ -96,6 +121,22 @@ fn parse_syntax_options(attrs: &[Attribute]) -> Result<Syntax> {
                 error!("The syntax has been set as a `keyword` or `token`.");
             }
             c = Some(meta.value()?.parse()?);
+        } else if ident == "memo" {
+            memo = Some(ident.clone());
+        } else if ident == "precedence" {
+            precedence = Some(ident.clone());
+        } else if ident == "context" {
+            context = Some(meta.value()?.parse()?);
+        } else if ident == "trivia" {
+            if no_trivia.is_some() {
+                error!("The syntax has been set as `no_trivia`.");
+            }
+            trivia = Some(meta.value()?.parse()?);
+        } else if ident == "no_trivia" {
+            if trivia.is_some() {
+                error!("The syntax has been set with a `trivia`.");
+            }
+            no_trivia = Some(ident.clone());
         } else {
             error!("Unsupport macro `syntax` option `{}`.", ident);
         }
@@ -112,6 +153,11 @@ fn parse_syntax_options(attrs: &[Attribute]) -> Result<Syntax> {
             keyword,
             token,
             c,
+            memo,
+            precedence,
+            context,
+            trivia,
+            no_trivia,
         })
     } else {
         Ok(Syntax {
@@ -119,6 +165,11 @@ fn parse_syntax_options(attrs: &[Attribute]) -> Result<Syntax> {
             keyword,
             token,
             c,
+            memo,
+            precedence,
+            context,
+            trivia,
+            no_trivia,
             ..Default::default()
         })
     }
@@ -131,6 +182,11 @@ fn derive_syntax_for_enum(item: ItemEnum) -> Result<proc_macro2::TokenStream> {
         keyword,
         token,
         c,
+        memo,
+        precedence,
+        context,
+        trivia,
+        no_trivia,
     } = parse_syntax_options(&item.attrs)?;
 
     match (keyword, token, c) {
@@ -166,109 +222,266 @@ fn derive_syntax_for_enum(item: ItemEnum) -> Result<proc_macro2::TokenStream> {
         quote! {}
     };
 
-    let (impl_generic, type_generic, where_clause) = item.generics.split_for_impl();
+    let generics = with_input_generic(&item.generics, &ty_input);
+    let (impl_generic, _, where_clause) = generics.split_for_impl();
+    let (_, type_generic, _) = item.generics.split_for_impl();
 
-    let (fields, to_spans): (Vec<_>, Vec<_>) = item
+    let options = item
         .variants
         .iter()
-        .map(|varint| {
+        .map(|varint| Ok(parse_variant_options(varint)?.unwrap_or_default()))
+        .collect::<Result<Vec<_>>>()?;
+
+    if options.iter().any(|options| options.keyword.is_some()) {
+        if let Some(peek) = options.iter().find_map(|options| options.peek.as_ref()) {
+            return Err(Error::new(
+                peek.span(),
+                "`peek` is not supported by a keyword enumeration, it dispatches on the keywords.",
+            ));
+        }
+
+        if let Some(memo) = &memo {
+            return Err(Error::new(
+                memo.span(),
+                "Deriving `memo` for a keyword enumeration is not supported.",
+            ));
+        }
+
+        let parse = derive_keyword_enum(&item, &options, &map_err)?;
+        let parse = with_trivia(parse, &ty_input, trivia.as_ref(), no_trivia.is_some());
+        let try_filter = parse_try_filter(&item.attrs)?;
+        let parse = with_try_filter(parse, &ty_input, &ident_str, try_filter.as_ref(), &map_err);
+        let parse = with_context(parse, &ty_input, context.as_ref());
+        let operator = derive_operator_for_enum(&item, &generics, &ty_input, precedence.as_ref())?;
+
+        let to_spans = item.variants.iter().map(|varint| {
             let variant_ident = &varint.ident;
 
-            let parse_fields = varint
-                .fields
-                .members()
-                .map(|member| match member {
-                    syn::Member::Named(ident) => {
-                        quote! {
-                            #ident: input.parse()?
-                        }
-                    }
-                    syn::Member::Unnamed(_) => {
-                        quote! {input.parse()?}
-                    }
-                })
-                .collect::<Vec<_>>();
-
-            let to_spans = varint
-                .fields
-                .members()
-                .map(|member| match member {
-                    syn::Member::Named(ident) => {
-                        quote! {
-                           #ident.to_span()
-                        }
-                    }
-                    syn::Member::Unnamed(index) => {
-                        let ident = format_ident!("ident_{}", index);
-                        quote! {
-                            #ident.to_span()
-                        }
+            if varint.fields.is_empty() {
+                quote! { Self::#variant_ident => parserc::Span::None }
+            } else {
+                quote! { Self::#variant_ident(input) => parserc::Input::to_span(input) }
+            }
+        });
+
+        return Ok(quote! {
+            #operator
+
+            impl #impl_generic parserc::syntax::Syntax<#ty_input> for #ident #type_generic #where_clause {
+                #[inline]
+                fn parse(input: &mut #ty_input) -> Result<Self, <#ty_input as parserc::Input>::Error> {
+                    #parse
+                }
+
+                #[inline]
+                fn to_span(&self) -> parserc::Span {
+                    match self {
+                        #(#to_spans),*
                     }
-                })
-                .collect::<Vec<_>>();
-
-            let parse = if let Fields::Named(_) = &varint.fields {
-                quote! {
-                    Ok(#ident::#variant_ident {
-                        #(#parse_fields),*
-                    })
                 }
-            } else {
-                quote! {
-                    Ok(#ident::#variant_ident(#(#parse_fields),*))
+            }
+        });
+    }
+
+    let left_recursive = item
+        .variants
+        .iter()
+        .filter(|varint| is_left_recursive(ident, &varint.fields))
+        .collect::<Vec<_>>();
+
+    if !left_recursive.is_empty() {
+        if let Some(memo) = &memo {
+            return Err(Error::new(
+                memo.span(),
+                "Deriving `memo` for a left-recursive enumeration is not supported.",
+            ));
+        }
+
+        if left_recursive.len() == item.variants.len() {
+            return Err(Error::new(
+                item.ident.span(),
+                "A left-recursive enumeration requires at least one non left-recursive variant.",
+            ));
+        }
+    }
+
+    let seed = syn::Lifetime {
+        apostrophe: proc_macro2::Span::mixed_site(),
+        ident: local("seed"),
+    };
+
+    let [parser, value, memo_key, memo_variant] =
+        ["parser", "value", "memo_key", "memo_variant"].map(local);
+
+    // a seed variant `break`s out of the seed block of a left-recursive enumeration.
+    let on_success = if left_recursive.is_empty() {
+        quote! { return Ok(#value); }
+    } else {
+        quote! { break #seed #value; }
+    };
+
+    let mut fields = vec![];
+    let mut grows = vec![];
+    let mut to_spans = vec![];
+
+    for (index, varint) in item.variants.iter().enumerate() {
+        let variant_ident = &varint.ident;
+
+        let field_idents = field_locals(&varint.fields);
+        let parse_fields = derive_parse_fields(&varint.fields, &ty_input, &quote! {})?;
+        let field_inits = field_inits(varint.fields.members(), &field_idents);
+
+        let variant_to_spans = varint
+            .fields
+            .iter()
+            .zip(&field_idents)
+            .filter_map(|(field, ident)| field_to_span(field, quote! { #ident }))
+            .collect::<Vec<_>>();
+
+        let parse = quote! {
+            #(let #field_idents = #parse_fields;)*
+
+            Ok(#ident::#variant_ident { #(#field_inits),* })
+        };
+
+        // computed fields are not bound, they don't contribute to the span.
+        let field_patterns = varint
+            .fields
+            .iter()
+            .zip(varint.fields.members())
+            .zip(&field_idents)
+            .map(|((field, member), ident)| {
+                if is_spanned_field(field) {
+                    field_init(member, ident)
+                } else {
+                    quote! { #member: _ }
                 }
-            };
+            })
+            .collect::<Vec<_>>();
 
-            let field_idents = varint
-                .fields
-                .members()
-                .map(|member| match member {
-                    syn::Member::Named(ident) => ident,
-                    syn::Member::Unnamed(index) => format_ident!("ident_{}", index),
-                })
-                .collect::<Vec<_>>();
-
-            let match_arm = if let Fields::Named(_) = &varint.fields {
-                quote! { Self::#variant_ident { #(#field_idents),* } }
-            } else {
-                quote! { Self::#variant_ident ( #(#field_idents),* ) }
-            };
+        let match_arm = quote! { Self::#variant_ident { #(#field_patterns),* } };
+
+        let parse = if is_left_recursive(ident, &varint.fields) {
+            // a left-recursive variant peeks the input after `lhs`.
+            grows.push(with_peek(
+                derive_left_recursive_variant(&ty_input, varint)?,
+                options[index].peek.as_ref(),
+            ));
+            quote! {}
+        } else if memo.is_some() {
+            quote! {
+                if #memo_variant.is_none_or(|variant| variant == #index) {
+                    let #parser = | input: &mut #ty_input | {
+                            use parserc::syntax::InputSyntaxExt;
+                            #parse
+                    };
+
+                    if let Some(#value) = #parser.ok().parse(input)? {
+                        if let Some(table) = parserc::Input::memo_table(input) {
+                            table.insert(#memo_key, Some(parserc::MemoEntry { variant: #index, end: parserc::Input::start(input) }));
+                        }
 
-            let parse = quote! {
-                let parser = | input: &mut #ty_input | {
+                        return Ok(#value);
+                    }
+                }
+            }
+        } else {
+            quote! {
+                let #parser = | input: &mut #ty_input | {
                         use parserc::syntax::InputSyntaxExt;
                         #parse
                 };
 
-                if let Some(value) = parser.ok().parse(input)? {
-                    return Ok(value);
+                if let Some(#value) = #parser.ok().parse(input)? {
+                    #on_success
                 }
-            };
+            }
+        };
 
-            let to_span = quote! {
-                #match_arm => {
-                    let mut lhs = parserc::Span::None;
-                    #(
-                        lhs = lhs.union(&#to_spans);
-                    )*
+        let to_span = quote! {
+            #match_arm => {
+                // fields are bound by their own names, so don't introduce any local variable here.
+                parserc::Span::None #(.union(&#variant_to_spans))*
+            }
+        };
+
+        fields.push(with_peek(parse, options[index].peek.as_ref()));
+        to_spans.push(to_span);
+    }
 
-                    lhs
+    let error = quote! {
+        Err(parserc::Kind::Syntax(#ident_str,parserc::ControlFlow::Recovable,input.to_span()).into())#map_err
+    };
+
+    // a cached failure returns immediately, a cached success only replays the matched variant.
+    let (memo_lookup, memo_failed) = if memo.is_some() {
+        (
+            quote! {
+                let #memo_key = parserc::MemoKey::new::<Self, _>(input);
+                let #memo_variant = match parserc::Input::memo_table(input).and_then(|table| table.get(&#memo_key)) {
+                    Some(None) => return #error,
+                    Some(Some(entry)) => Some(entry.variant),
+                    None => None,
+                };
+            },
+            quote! {
+                if let Some(table) = parserc::Input::memo_table(input) {
+                    table.insert(#memo_key, None);
                 }
+            },
+        )
+    } else {
+        (quote! {}, quote! {})
+    };
+
+    // parse a seed from non left-recursive variants, then grow it with left-recursive variants.
+    let parse = if left_recursive.is_empty() {
+        quote! {
+            #memo_lookup
+            #(#fields)*
+            #memo_failed
+
+            #error
+        }
+    } else {
+        let lhs = local("lhs");
+        let start = local("start");
+
+        quote! {
+            let mut #lhs = #seed: {
+                #(#fields)*
+
+                return #error;
             };
 
-            (parse, to_span)
-        })
-        .unzip();
+            loop {
+                let #start = parserc::Input::start(input);
+
+                #(#grows)*
+
+                break;
+            }
+
+            Ok(#lhs)
+        }
+    };
+
+    let operator = derive_operator_for_enum(&item, &generics, &ty_input, precedence.as_ref())?;
+
+    let parse = with_trivia(parse, &ty_input, trivia.as_ref(), no_trivia.is_some());
+    let try_filter = parse_try_filter(&item.attrs)?;
+    let parse = with_try_filter(parse, &ty_input, &ident_str, try_filter.as_ref(), &map_err);
+    let parse = with_context(parse, &ty_input, context.as_ref());
 
     Ok(quote! {
+        #operator
+
         impl #impl_generic parserc::syntax::Syntax<#ty_input> for #ident #type_generic #where_clause {
             #[inline]
             fn parse(input: &mut #ty_input) -> Result<Self, <#ty_input as parserc::Input>::Error> {
                 use parserc::syntax::InputSyntaxExt;
                 use parserc::Parser;
-                #(#fields)*
-
-                Err(parserc::Kind::Syntax(#ident_str,parserc::ControlFlow::Recovable,input.to_span()).into())#map_err
+                #parse
             }
 
             #[inline]
@@ -281,6 +494,473 @@ fn derive_syntax_for_enum(item: ItemEnum) -> Result<proc_macro2::TokenStream> {
     })
 }
 
+/// Variant options of an enumeration: `keyword`, `peek` or the operator options of a `#[syntax(precedence)]` enumeration.
+#[derive(Default)]
+struct VariantOptions {
+    keyword: Option<Lit>,
+    peek: Option<Pat>,
+    prefix: Option<Lit>,
+    infix: Option<Lit>,
+    assoc: Option<Ident>,
+    postfix: Option<Lit>,
+}
+
+fn parse_variant_options(varint: &Variant) -> Result<Option<VariantOptions>> {
+    let Some(syntax) = varint
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("syntax"))
+    else {
+        return Ok(None);
+    };
+
+    let meta_list = match &syntax.meta {
+        syn::Meta::List(meta_list) => meta_list,
+        _ => return Err(Error::new(syntax.span(), "Expect `syntax(...)`")),
+    };
+
+    let mut options = VariantOptions::default();
+
+    let parser = syn::meta::parser(|meta| {
+        let Some(ident) = meta.path.get_ident() else {
+            return Err(meta.error("Unsupport variant `syntax` option."));
+        };
+
+        if ident == "keyword" {
+            options.keyword = Some(meta.value()?.parse()?);
+        } else if ident == "peek" {
+            options.peek = Some(Pat::parse_multi_with_leading_vert(meta.value()?)?);
+        } else if ident == "prefix" {
+            options.prefix = Some(meta.value()?.parse()?);
+        } else if ident == "infix" {
+            options.infix = Some(meta.value()?.parse()?);
+        } else if ident == "postfix" {
+            options.postfix = Some(meta.value()?.parse()?);
+        } else if ident == "assoc" {
+            let assoc: Ident = meta.value()?.parse()?;
+
+            if assoc != "left" && assoc != "right" {
+                return Err(Error::new(assoc.span(), "Expect `left` or `right`."));
+            }
+
+            options.assoc = Some(assoc);
+        } else {
+            return Err(meta.error(format_args!(
+                "Unsupport variant `syntax` option `{}`.",
+                ident
+            )));
+        }
+
+        Ok(())
+    });
+
+    parser.parse2(meta_list.tokens.to_token_stream())?;
+
+    if let (Some(assoc), None) = (&options.assoc, &options.infix) {
+        return Err(Error::new(
+            assoc.span(),
+            "`assoc` is only valid for an `infix` operator.",
+        ));
+    }
+
+    Ok(Some(options))
+}
+
+/// Generate `parserc::pratt::Operator` for an enumeration with `#[syntax(precedence)]`.
+fn derive_operator_for_enum(
+    item: &ItemEnum,
+    generics: &Generics,
+    ty_input: &Type,
+    precedence: Option<&Ident>,
+) -> Result<proc_macro2::TokenStream> {
+    let mut prefix = vec![];
+    let mut infix = vec![];
+    let mut postfix = vec![];
+
+    for varint in &item.variants {
+        let Some(options) = parse_variant_options(varint)? else {
+            continue;
+        };
+
+        if options.prefix.is_none() && options.infix.is_none() && options.postfix.is_none() {
+            continue;
+        }
+
+        if precedence.is_none() {
+            return Err(Error::new(
+                varint.span(),
+                "Operator options require `#[syntax(precedence)]` on the enumeration.",
+            ));
+        }
+
+        let variant_ident = &varint.ident;
+
+        if let Some(power) = options.prefix {
+            prefix.push(quote! { Self::#variant_ident { .. } => Some(#power) });
+        }
+
+        if let Some(power) = options.infix {
+            let assoc = match options.assoc {
+                Some(assoc) if assoc == "right" => quote! { parserc::pratt::Assoc::Right },
+                _ => quote! { parserc::pratt::Assoc::Left },
+            };
+
+            infix.push(quote! { Self::#variant_ident { .. } => Some((#power, #assoc)) });
+        }
+
+        if let Some(power) = options.postfix {
+            postfix.push(quote! { Self::#variant_ident { .. } => Some(#power) });
+        }
+    }
+
+    if precedence.is_none() {
+        return Ok(quote! {});
+    }
+
+    let variants = item.variants.len();
+
+    // only generate positions used by at least one variant, the others keep the default `None`.
+    let position =
+        |name: &str, ty: proc_macro2::TokenStream, arms: Vec<proc_macro2::TokenStream>| {
+            if arms.is_empty() {
+                return quote! {};
+            }
+
+            let fallback = if arms.len() < variants {
+                quote! { _ => None, }
+            } else {
+                quote! {}
+            };
+
+            let name = format_ident!("{}", name);
+
+            quote! {
+                #[inline]
+                fn #name(&self) -> Option<#ty> {
+                    match self {
+                        #(#arms,)*
+                        #fallback
+                    }
+                }
+            }
+        };
+
+    let prefix = position("prefix", quote! { u8 }, prefix);
+    let infix = position("infix", quote! { (u8, parserc::pratt::Assoc) }, infix);
+    let postfix = position("postfix", quote! { u8 }, postfix);
+
+    let ident = &item.ident;
+    let (impl_generic, _, where_clause) = generics.split_for_impl();
+    let (_, type_generic, _) = item.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generic parserc::pratt::Operator<#ty_input> for #ident #type_generic #where_clause {
+            #prefix
+            #infix
+            #postfix
+        }
+    })
+}
+
+/// Guard the generated `parse` code of a variant with its `peek` pattern,
+/// the variant is only tried if the next input item matches the pattern.
+///
+/// At the end of a partial input the variant is tried, so it can return an `Incomplete` error.
+fn with_peek(parse: proc_macro2::TokenStream, peek: Option<&Pat>) -> proc_macro2::TokenStream {
+    let Some(peek) = peek else {
+        return parse;
+    };
+
+    quote! {
+        if match parserc::Input::iter(input).next() {
+            Some(#peek) => true,
+            None => parserc::Input::is_partial(input),
+            _ => false,
+        } {
+            #parse
+        }
+    }
+}
+
+/// Returns `generics` with the input type parameter `I` declared, if the enumeration uses the default
+/// input type `I` without declaring it, e.g. `enum Op { Add, Sub }`.
+fn with_input_generic(generics: &Generics, ty_input: &Type) -> Generics {
+    let mut generics = generics.clone();
+
+    let Type::Path(path) = ty_input else {
+        return generics;
+    };
+
+    let Some(ident) = path.path.get_ident() else {
+        return generics;
+    };
+
+    if generics.type_params().all(|param| param.ident != *ident) {
+        generics
+            .params
+            .push(parse_quote! { #ident: parserc::Input<Item = u8> + parserc::AsBytes });
+    }
+
+    generics
+}
+
+/// A byte trie of keywords, a node is terminal if a keyword ends at it.
+#[derive(Default)]
+struct Trie {
+    /// `(variant index, keyword length)` of the keyword ends at this node.
+    terminal: Option<(usize, usize)>,
+    children: BTreeMap<u8, Trie>,
+}
+
+impl Trie {
+    fn insert(&mut self, keyword: &[u8], variant: usize) -> bool {
+        let node = keyword
+            .iter()
+            .fold(self, |node, byte| node.children.entry(*byte).or_default());
+
+        node.terminal.replace((variant, keyword.len())).is_none()
+    }
+
+    /// Generate an expression returns the `(variant index, keyword length)` of the longest keyword
+    /// matching `bytes` from the offset `depth`.
+    fn to_match(
+        &self,
+        depth: usize,
+        incomplete: &proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let terminal = match self.terminal {
+            Some((variant, len)) => quote! { Some((#variant, #len)) },
+            None => quote! { None },
+        };
+
+        if self.children.is_empty() {
+            return terminal;
+        }
+
+        let arms = self.children.iter().map(|(byte, child)| {
+            let child = child.to_match(depth + 1, incomplete);
+            quote! { Some(#byte) => #child, }
+        });
+
+        let bytes = local("bytes");
+        let partial = local("partial");
+
+        quote! {
+            match #bytes.get(#depth) {
+                #(#arms)*
+                // a longer keyword may follow on a partial input.
+                None if #partial => #incomplete,
+                _ => None,
+            }
+            .or(#terminal)
+        }
+    }
+}
+
+/// Generate the `parse` body of a keyword enumeration, the longest matching keyword decides the variant.
+///
+/// A unit variant doesn't keep the matched input, so its `to_span` is `Span::None`. It's rejected for
+/// prefix and postfix operators, whose span is needed by `parserc::pratt::Expr::to_span`.
+fn derive_keyword_enum(
+    item: &ItemEnum,
+    options: &[VariantOptions],
+    map_err: &proc_macro2::TokenStream,
+) -> Result<proc_macro2::TokenStream> {
+    let ident_str = item.ident.to_string();
+
+    let mut trie = Trie::default();
+    let mut constructs = vec![];
+    let mut labels = vec![];
+
+    for (index, (varint, options)) in item.variants.iter().zip(options).enumerate() {
+        let Some(keyword) = &options.keyword else {
+            return Err(Error::new(
+                varint.span(),
+                "A variant of a keyword enumeration requires `#[syntax(keyword = ...)]`.",
+            ));
+        };
+
+        let text = lit_to_string(keyword);
+
+        if text.is_empty() {
+            return Err(Error::new(keyword.span(), "The keyword can't be empty."));
+        }
+
+        if !trie.insert(text.as_bytes(), index) {
+            return Err(Error::new(keyword.span(), "Duplicate keyword."));
+        }
+
+        let variant_ident = &varint.ident;
+        let len = local("len");
+
+        let construct = match &varint.fields {
+            Fields::Unit if options.prefix.is_some() || options.postfix.is_some() => {
+                return Err(Error::new(
+                    varint.span(),
+                    "A prefix or postfix keyword operator keeps the matched input for its span, e.g. `Neg(I)`.",
+                ));
+            }
+            // a unit variant drops the matched input.
+            Fields::Unit => quote! {{
+                parserc::Input::split_to(input, #len);
+                Self::#variant_ident
+            }},
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                quote! { Self::#variant_ident(parserc::Input::split_to(input, #len)) }
+            }
+            fields => {
+                return Err(Error::new(
+                    fields.span(),
+                    "A keyword variant is either a unit variant or keeps the matched input, e.g. `Add(I)`.",
+                ));
+            }
+        };
+
+        constructs.push(quote! { #index => #construct, });
+        labels.push(format!("`{}`", text));
+    }
+
+    let incomplete = quote! {
+        return Err(parserc::Kind::Syntax(
+            #ident_str,
+            parserc::ControlFlow::Incomplete,
+            parserc::Input::needed_at(input, 1),
+        )
+        .into())#map_err
+    };
+
+    let matched = trie.to_match(0, &incomplete);
+
+    let [partial, bytes, variant, len, value] =
+        ["partial", "bytes", "variant", "len", "value"].map(local);
+
+    Ok(quote! {
+        parserc::Input::skip_trivia(input)#map_err?;
+
+        let #partial = parserc::Input::is_partial(input);
+        let #bytes = parserc::AsBytes::as_bytes(input);
+
+        let Some((#variant, #len)) = #matched else {
+            #(parserc::Input::report_expected(input, #labels);)*
+
+            return Err(parserc::Kind::Syntax(
+                #ident_str,
+                parserc::ControlFlow::Recovable,
+                parserc::Input::to_span(input),
+            )
+            .into())#map_err;
+        };
+
+        let #value = match #variant {
+            #(#constructs)*
+            _ => unreachable!(),
+        };
+
+        parserc::Input::skip_trivia(input)#map_err?;
+
+        Ok(#value)
+    })
+}
+
+/// Returns true if the first field of a variant is `Box<Self>`.
+fn is_left_recursive(ident: &Ident, fields: &Fields) -> bool {
+    let Some(field) = fields.iter().next() else {
+        return false;
+    };
+
+    let Type::Path(ty) = &field.ty else {
+        return false;
+    };
+
+    let Some(segment) = ty.path.segments.last() else {
+        return false;
+    };
+
+    if segment.ident != "Box" {
+        return false;
+    }
+
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return false;
+    };
+
+    let Some(GenericArgument::Type(Type::Path(inner))) = args.args.first() else {
+        return false;
+    };
+
+    inner.path.is_ident("Self")
+        || inner
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == *ident)
+}
+
+/// Generate the code that grows the parsed `lhs` with a left-recursive variant.
+///
+/// Variants grow `lhs` in declaration order and left-associatively, there is no operator precedence:
+/// `1+2*3` parses as `(1+2)*3`. Operators with precedence are `#[syntax(precedence)]` enumerations
+/// parsed by `parserc::pratt::Expr`.
+fn derive_left_recursive_variant(
+    ty_input: &Type,
+    varint: &Variant,
+) -> Result<proc_macro2::TokenStream> {
+    let variant_ident = &varint.ident;
+
+    if varint.fields.len() < 2 {
+        return Err(Error::new(
+            varint.span(),
+            "A left-recursive variant requires at least one field after the recursive one.",
+        ));
+    }
+
+    let recursive = varint.fields.iter().next().unwrap();
+
+    if let Some(attr) = recursive.attrs.iter().find(|attr| {
+        ["fatal", "from", "syntax"]
+            .iter()
+            .any(|name| attr.path().is_ident(name))
+    }) {
+        return Err(Error::new(
+            attr.span(),
+            "The left-recursive field doesn't accept field options.",
+        ));
+    }
+
+    let members = varint.fields.members().collect::<Vec<_>>();
+    let mut locals = field_locals(&varint.fields);
+    let rest_idents = locals.split_off(1);
+    let rest_parses = derive_parse_fields(varint.fields.iter().skip(1), ty_input, &quote! {})?;
+
+    // locals shared with the loop in `derive_syntax_for_enum`.
+    let lhs = local("lhs");
+    let start = local("start");
+    let parser = local("parser");
+
+    let first = &members[0];
+    let rest_inits = field_inits(members[1..].iter().cloned(), &rest_idents);
+    let construct = quote! {
+        Self::#variant_ident { #first: Box::new(#lhs), #(#rest_inits),* }
+    };
+
+    Ok(quote! {
+        let #parser = | input: &mut #ty_input | {
+            use parserc::syntax::InputSyntaxExt;
+            #(
+                let #rest_idents = #rest_parses;
+            )*
+
+            Ok((#(#rest_idents),*,))
+        };
+
+        if let Some((#(#rest_idents),*,)) = #parser.ok().parse(input)? {
+            #lhs = #construct;
+            parserc::Input::ensure_progress(input, #start)?;
+            continue;
+        }
+    })
+}
+
 fn derive_syntax_for_struct(item: ItemStruct) -> Result<proc_macro2::TokenStream> {
     let Syntax {
         ty_input,
@@ -288,8 +968,27 @@ fn derive_syntax_for_struct(item: ItemStruct) -> Result<proc_macro2::TokenStream
         keyword,
         token,
         c,
+        memo,
+        precedence,
+        context,
+        trivia,
+        no_trivia,
     } = parse_syntax_options(&item.attrs)?;
 
+    if let Some(memo) = memo {
+        return Err(Error::new(
+            memo.span(),
+            "Deriving `memo` for a structure is not supported.",
+        ));
+    }
+
+    if let Some(precedence) = precedence {
+        return Err(Error::new(
+            precedence.span(),
+            "Deriving `precedence` for a structure is not supported.",
+        ));
+    }
+
     let ident = &item.ident;
 
     let map_err = if let Some(map_err) = map_err {
@@ -302,114 +1001,410 @@ fn derive_syntax_for_struct(item: ItemStruct) -> Result<proc_macro2::TokenStream
 
     let (impl_generic, type_generic, where_clause) = item.generics.split_for_impl();
 
-    let parse_fields = item
-        .fields
-        .members()
-        .map(|member| match member {
-            syn::Member::Named(ident) => {
-                quote! {
-                    #ident: input.parse()#map_err?
-                }
-            }
-            syn::Member::Unnamed(_) => {
-                quote! {input.parse()#map_err?}
-            }
-        })
-        .collect::<Vec<_>>();
+    let field_idents = field_locals(&item.fields);
+    let parse_fields = derive_parse_fields(&item.fields, &ty_input, &map_err)?;
+    let members = item.fields.members().collect::<Vec<_>>();
+    let field_inits = field_inits(members.iter().cloned(), &field_idents);
 
     let to_spans = item
         .fields
-        .members()
-        .map(|member| match member {
-            syn::Member::Named(ident) => {
-                quote! {
-                   self.#ident.to_span()
-                }
-            }
-            syn::Member::Unnamed(index) => {
-                quote! {
-                    self.#index.to_span()
-                }
-            }
-        })
+        .iter()
+        .zip(&members)
+        .filter_map(|(field, member)| field_to_span(field, quote! { &self.#member }))
         .collect::<Vec<_>>();
 
-    let parse = if item.semi_token.is_some() {
-        quote! {
-            Ok(Self(#(#parse_fields),*))
-        }
+    let parse = quote! {
+        #(let #field_idents = #parse_fields;)*
+
+        Ok(Self { #(#field_inits),* })
+    };
+
+    // tokens report their name to `parserc::Furthest` inputs on failure.
+    let label = match (&keyword, &c) {
+        (Some(lit), _) | (_, Some(lit)) => format!("`{}`", lit_to_string(lit)),
+        _ => ident.to_string(),
+    };
+
+    // a token struct wraps the matched input.
+    let token_parser = if let Some(keyword) = keyword {
+        Some(quote! { parserc::keyword(#keyword) })
+    } else if let Some(token) = token {
+        Some(quote! { parserc::take_while_range_from(1, #token) })
     } else {
-        quote! {
-            Ok(Self {
-                #(#parse_fields),*
-            })
-        }
+        c.map(|c| quote! { parserc::next(#c) })
     };
 
-    if let Some(keyword) = keyword {
-        Ok(quote! {
-            impl #impl_generic parserc::syntax::Syntax<#ty_input> for #ident #type_generic #where_clause {
-                #[inline]
-                fn parse(input: &mut #ty_input) -> Result<Self, <#ty_input as parserc::Input>::Error> {
-                    use parserc::Parser;
-                    parserc::keyword(#keyword).map(|input| Self(input)).parse(input)
-                }
+    let (parse, to_span) = if let Some(token_parser) = token_parser {
+        let token = local("token");
 
-                #[inline]
-                fn to_span(&self) -> parserc::Span {
-                    self.0.to_span()
-                }
+        (
+            quote! {
+                parserc::Input::skip_trivia(input)#map_err?;
+
+                let #token = #token_parser
+                    .map(|input| Self(input))
+                    .parse(input)
+                    .inspect_err(|_| parserc::Input::report_expected(input, #label))?;
+
+                parserc::Input::skip_trivia(input)#map_err?;
+
+                Ok(#token)
+            },
+            quote! { self.0.to_span() },
+        )
+    } else {
+        (
+            parse,
+            quote! {
+                let mut lhs = parserc::Span::None;
+                #(
+                    lhs = lhs.union(&#to_spans);
+                )*
+
+                lhs
+            },
+        )
+    };
+
+    let parse = with_trivia(parse, &ty_input, trivia.as_ref(), no_trivia.is_some());
+    let try_filter = parse_try_filter(&item.attrs)?;
+    let parse = with_try_filter(
+        parse,
+        &ty_input,
+        &ident.to_string(),
+        try_filter.as_ref(),
+        &map_err,
+    );
+    let parse = with_context(parse, &ty_input, context.as_ref());
+
+    Ok(quote! {
+        impl #impl_generic parserc::syntax::Syntax<#ty_input> for #ident #type_generic #where_clause {
+            #[inline]
+            fn parse(input: &mut #ty_input) -> Result<Self, <#ty_input as parserc::Input>::Error> {
+                use parserc::syntax::InputSyntaxExt;
+                use parserc::Parser;
+                #parse
             }
-        })
-    } else if let Some(token) = token {
-        Ok(quote! {
-            impl #impl_generic parserc::syntax::Syntax<#ty_input> for #ident #type_generic #where_clause {
-                #[inline]
-                fn parse(input: &mut #ty_input) -> Result<Self, <#ty_input as parserc::Input>::Error> {
-                    use parserc::Parser;
-                    parserc::take_while_range_from(1, #token).map(|input| Self(input)).parse(input)
-                }
 
-                #[inline]
-                fn to_span(&self) -> parserc::Span {
-                    self.0.to_span()
-                }
+            #[inline]
+            fn to_span(&self) -> parserc::Span {
+                #to_span
             }
+        }
+    })
+}
+
+/// Returns the local variables a parsed struct or variant binds its fields to,
+/// named fields are bound by their names, so `#[syntax(with = expr)]` can use the fields before it.
+fn field_locals(fields: &Fields) -> Vec<Ident> {
+    fields
+        .members()
+        .map(|member| match member {
+            // don't shadow the parsed input.
+            syn::Member::Named(ident) if ident == "input" => local("input"),
+            syn::Member::Named(ident) => ident,
+            syn::Member::Unnamed(index) => local(&format!("field_{}", index.index)),
         })
-    } else if let Some(c) = c {
-        Ok(quote! {
-            impl #impl_generic parserc::syntax::Syntax<#ty_input> for #ident #type_generic #where_clause {
-                #[inline]
-                fn parse(input: &mut #ty_input) -> Result<Self, <#ty_input as parserc::Input>::Error> {
-                    use parserc::Parser;
-                    parserc::next(#c).map(|input| Self(input)).parse(input)
+        .collect()
+}
+
+/// Returns `member: local`, or the shorthand `local` if it has the same name.
+fn field_init(member: syn::Member, local: &Ident) -> proc_macro2::TokenStream {
+    match member {
+        // the shorthand keeps the hygiene of `local`.
+        syn::Member::Named(ident) if ident == *local => quote! { #local },
+        member => quote! { #member: #local },
+    }
+}
+
+fn field_inits(
+    members: impl IntoIterator<Item = syn::Member>,
+    locals: &[Ident],
+) -> Vec<proc_macro2::TokenStream> {
+    members
+        .into_iter()
+        .zip(locals)
+        .map(|(member, local)| field_init(member, local))
+        .collect()
+}
+
+/// Field options: `#[fatal]`, `#[from(Type)]` and `#[syntax(parser = .., default, skip, with = ..)]`.
+#[derive(Default)]
+struct FieldOptions {
+    fatal: bool,
+    from: Option<Type>,
+    parser: Option<Expr>,
+    default: bool,
+    with: Option<Expr>,
+}
+
+impl FieldOptions {
+    /// Returns true if the field is parsed from the input and contributes to `to_span`.
+    fn is_spanned(&self) -> bool {
+        !self.default && self.with.is_none()
+    }
+}
+
+fn parse_field_options(field: &Field) -> Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+
+    for attr in &field.attrs {
+        if attr.path().is_ident("fatal") {
+            attr.meta.require_path_only()?;
+            options.fatal = true;
+        } else if attr.path().is_ident("from") {
+            options.from = Some(attr.parse_args()?);
+        } else if attr.path().is_ident("syntax") {
+            attr.parse_nested_meta(|meta| {
+                let Some(ident) = meta.path.get_ident() else {
+                    return Err(meta.error("Unsupport field `syntax` option."));
+                };
+
+                if options.parser.is_some() || options.default || options.with.is_some() {
+                    return Err(meta.error(
+                        "A field accepts only one of `parser`, `default`, `skip` and `with`.",
+                    ));
                 }
 
-                #[inline]
-                fn to_span(&self) -> parserc::Span {
-                    self.0.to_span()
+                if ident == "parser" {
+                    options.parser = Some(meta.value()?.parse()?);
+                } else if ident == "default" || ident == "skip" {
+                    options.default = true;
+                } else if ident == "with" {
+                    options.with = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(
+                        meta.error(format_args!("Unsupport field `syntax` option `{}`.", ident))
+                    );
                 }
-            }
-        })
+
+                Ok(())
+            })?;
+        }
+    }
+
+    let computed = options.parser.is_some() || options.default || options.with.is_some();
+
+    if let (Some(ty), true) = (&options.from, computed) {
+        return Err(Error::new(
+            ty.span(),
+            "`from` can't be combined with `parser`, `default`, `skip` or `with`.",
+        ));
+    }
+
+    Ok(options)
+}
+
+/// Returns true if the field is parsed from the input and contributes to `to_span`.
+fn is_spanned_field(field: &Field) -> bool {
+    parse_field_options(field).is_ok_and(|options| options.is_spanned())
+}
+
+/// Returns the `to_span` expression of a field referenced by `value`, `None` if it doesn't contribute to the span.
+///
+/// The value of a `#[from(Type)]` or `#[syntax(parser = expr)]` field is not a syntax,
+/// it returns the span by `parserc::syntax::ToSpan`.
+fn field_to_span(
+    field: &Field,
+    value: proc_macro2::TokenStream,
+) -> Option<proc_macro2::TokenStream> {
+    let options = parse_field_options(field).ok()?;
+
+    if !options.is_spanned() {
+        return None;
+    }
+
+    if options.from.is_some() || options.parser.is_some() {
+        Some(quote! { parserc::syntax::ToSpan::to_span(#value) })
     } else {
-        Ok(quote! {
-            impl #impl_generic parserc::syntax::Syntax<#ty_input> for #ident #type_generic #where_clause {
-                #[inline]
-                fn parse(input: &mut #ty_input) -> Result<Self, <#ty_input as parserc::Input>::Error> {
-                    use parserc::syntax::InputSyntaxExt;
-                    #parse
-                }
+        Some(quote! { (#value).to_span() })
+    }
+}
 
-                #[inline]
-                fn to_span(&self) -> parserc::Span {
-                    let mut lhs = parserc::Span::None;
-                    #(
-                        lhs = lhs.union(&#to_spans);
-                    )*
+/// Generate the expressions of `fields`, bound in order to local variables named after the fields.
+///
+/// The fields after a `#[fatal]` field convert `non-fatal` errors into fatal errors, like a cut,
+/// a `#[from(Type)]` field parses `Type` and converts it with `Into`,
+/// a `#[syntax(parser = expr)]` field is parsed by the `Parser` `expr`,
+/// a `#[syntax(default)]` / `#[syntax(skip)]` field isn't parsed and gets `Default::default()`,
+/// and a `#[syntax(with = expr)]` field is computed by `expr` from the fields before it.
+fn derive_parse_fields<'a>(
+    fields: impl IntoIterator<Item = &'a Field>,
+    ty_input: &Type,
+    map_err: &proc_macro2::TokenStream,
+) -> Result<Vec<proc_macro2::TokenStream>> {
+    let mut fatal = false;
+    let mut parses = vec![];
 
-                    lhs
-                }
+    for field in fields {
+        let options = parse_field_options(field)?;
+
+        // the cut takes effect from the next field.
+        let cut = fatal;
+        fatal |= options.fatal;
+
+        if options.default {
+            parses.push(quote! { Default::default() });
+            continue;
+        }
+
+        if let Some(with) = options.with {
+            parses.push(quote! { #with });
+            continue;
+        }
+
+        let fatal = if cut {
+            quote! { .fatal() }
+        } else {
+            quote! {}
+        };
+
+        let skip = quote! { parserc::Input::skip_trivia(input)#map_err?; };
+
+        let parse = match (&options.parser, &options.from, fatal.is_empty()) {
+            (None, None, true) => quote! { input.parse() },
+            (None, Some(ty), true) => quote! { input.parse::<#ty>() },
+            (Some(parser), _, _) => quote! { parserc::Parser::parse(&mut (#parser) #fatal, input) },
+            (None, ty, false) => {
+                let ty = ty.as_ref().unwrap_or(&field.ty);
+                quote! { <#ty as parserc::syntax::Syntax<#ty_input>>::into_parser() #fatal .parse(input) }
             }
-        })
+        };
+
+        let into = if options.from.is_some() {
+            quote! { .map(Into::into) }
+        } else {
+            quote! {}
+        };
+
+        parses.push(quote! {{
+            #skip
+            #parse #into #map_err?
+        }});
+    }
+
+    Ok(parses)
+}
+
+/// Returns the predicate of a `#[try_filter(expr)]` attribute.
+fn parse_try_filter(attrs: &[Attribute]) -> Result<Option<Expr>> {
+    attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("try_filter"))
+        .map(|attr| attr.parse_args())
+        .transpose()
+}
+
+/// Wrap the generated `parse` body to reject the parsed node if the `try_filter` predicate returns false.
+///
+/// The rejection is a `recovable` `Kind::Syntax` error that points to the consumed input.
+fn with_try_filter(
+    parse: proc_macro2::TokenStream,
+    ty_input: &Type,
+    ident: &str,
+    try_filter: Option<&Expr>,
+    map_err: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    let Some(try_filter) = try_filter else {
+        return parse;
+    };
+
+    let [start, parser, value] = ["start", "parser", "value"].map(local);
+
+    quote! {
+        // gives the predicate an expected type, so closure parameters need no annotation.
+        fn try_filter<T>(value: &T, f: impl FnOnce(&T) -> bool) -> bool {
+            f(value)
+        }
+
+        let #start = parserc::Input::start(input);
+
+        let #parser = |input: &mut #ty_input| -> Result<Self, <#ty_input as parserc::Input>::Error> {
+            #parse
+        };
+
+        let #value = #parser(input)?;
+
+        if !try_filter(&#value, #try_filter) {
+            return Err(parserc::Kind::Syntax(
+                #ident,
+                parserc::ControlFlow::Recovable,
+                parserc::Span::Range(#start..parserc::Input::start(input)),
+            )
+            .into())#map_err;
+        }
+
+        Ok(#value)
+    }
+}
+
+/// Wrap the generated `parse` body to install the `trivia` skipper, or none for a `no_trivia` syntax,
+/// for the syntaxes parsed inside it, and restore the previous one afterwards.
+fn with_trivia(
+    parse: proc_macro2::TokenStream,
+    ty_input: &Type,
+    trivia: Option<&Type>,
+    no_trivia: bool,
+) -> proc_macro2::TokenStream {
+    let skip = match (trivia, no_trivia) {
+        (Some(trivia), _) => quote! {
+            Some(parserc::SkipTrivia::new::<#trivia>(|input: &mut #ty_input| {
+                parserc::Parser::parse(
+                    &mut parserc::Parser::ok(<#trivia as parserc::syntax::Syntax<#ty_input>>::into_parser()),
+                    input,
+                )
+                .map(|_| ())
+            }))
+        },
+        (None, true) => quote! { None },
+        (None, false) => return parse,
+    };
+
+    let [previous, parser, result] = ["previous", "parser", "result"].map(local);
+
+    quote! {
+        let #previous = parserc::Input::replace_trivia(input, #skip);
+
+        let #parser = |input: &mut #ty_input| -> Result<Self, <#ty_input as parserc::Input>::Error> {
+            #parse
+        };
+
+        let #result = #parser(input);
+
+        parserc::Input::replace_trivia(input, #previous);
+
+        #result
+    }
+}
+
+/// Wrap the generated `parse` body to push the `context` frame onto its error.
+fn with_context(
+    parse: proc_macro2::TokenStream,
+    ty_input: &Type,
+    context: Option<&LitStr>,
+) -> proc_macro2::TokenStream {
+    let Some(context) = context else {
+        return parse;
+    };
+
+    let parser = local("parser");
+
+    quote! {
+        let #parser = |input: &mut #ty_input| -> Result<Self, <#ty_input as parserc::Input>::Error> {
+            #parse
+        };
+
+        #parser(input).map_err(|err| parserc::ParseError::context(err, #context))
+    }
+}
+
+/// Returns the text matched by a `keyword` or `char` literal.
+fn lit_to_string(lit: &Lit) -> String {
+    match lit {
+        Lit::Str(lit) => lit.value(),
+        Lit::ByteStr(lit) => String::from_utf8_lossy(&lit.value()).into_owned(),
+        Lit::Byte(lit) => char::from(lit.value()).to_string(),
+        Lit::Char(lit) => lit.value().to_string(),
+        lit => lit.to_token_stream().to_string(),
     }
 }
@@ -0,0 +1,204 @@
+//! Combinators for choosing between alternative parsers.
+
+use crate::{
+    Span,
+    errors::{ControlFlow, Kind, ParseError},
+    input::Input,
+    parser::Parser,
+};
+
+/// A group of alternative parsers, implemented for tuples `(P1, P2, ..., PN)`.
+pub trait Alt<I>
+where
+    I: Input,
+{
+    type Output;
+
+    /// Returns the output of the first branch that succeeds.
+    fn parse_first(&mut self, input: &mut I) -> Result<Self::Output, I::Error>;
+
+    /// Executes every branch and returns the output of the branch that consumed the most input.
+    ///
+    /// If several branches consume the same length, the first one wins.
+    fn parse_longest(&mut self, input: &mut I) -> Result<Self::Output, I::Error>;
+}
+
+/// Create a parser that tries each branch in order and returns the first success.
+///
+/// A [`fatal`](ControlFlow::Fatal) error raised by any branch stops the process.
+/// If all branches fail, returns a [`Kind::Alt`] error whose span covers all branch errors.
+#[inline]
+pub fn alt<I, A>(mut branches: A) -> impl Parser<I, Output = A::Output>
+where
+    I: Input,
+    A: Alt<I>,
+{
+    move |input: &mut I| branches.parse_first(input)
+}
+
+/// Create a parser that executes every branch and keeps the one that consumed the most input.
+///
+/// Errors are handled as for [`alt`].
+#[inline]
+pub fn alt_longest<I, A>(mut branches: A) -> impl Parser<I, Output = A::Output>
+where
+    I: Input,
+    A: Alt<I>,
+{
+    move |input: &mut I| branches.parse_longest(input)
+}
+
+/// Create the merged error returns when all branches fail.
+#[inline]
+fn alt_error<I>(input: &I, span: Span) -> I::Error
+where
+    I: Input,
+{
+    let span = match span {
+        Span::None => input.to_span_at(0),
+        span => span,
+    };
+
+    Kind::Alt(ControlFlow::Recovable, span).into()
+}
+
+/// Implement [`Alt`] for the tuple `(P0, P1, ...)` where every `P` is a [`Parser`] with the same output.
+macro_rules! impl_alt {
+    ($($parser:ident $index:tt),+) => {
+        impl<I, O, $($parser),+> Alt<I> for ($($parser,)+)
+        where
+            I: Input + Clone,
+            $($parser: Parser<I, Output = O>),+
+        {
+            type Output = O;
+
+            #[inline]
+            fn parse_first(&mut self, input: &mut I) -> Result<O, I::Error> {
+                let mut span = Span::None;
+
+                $(
+                    let mut try_input = input.clone();
+                    match self.$index.parse(&mut try_input) {
+                        Ok(output) => {
+                            *input = try_input;
+                            return Ok(output);
+                        }
+                        Err(err) if err.control_flow() == ControlFlow::Fatal => return Err(err),
+                        Err(err)
+                            if err.control_flow() == ControlFlow::Incomplete && input.is_partial() =>
+                        {
+                            return Err(err);
+                        }
+                        Err(err) => span = span.union(&err.to_span()),
+                    }
+                )+
+
+                Err(alt_error(input, span))
+            }
+
+            #[inline]
+            fn parse_longest(&mut self, input: &mut I) -> Result<O, I::Error> {
+                let mut span = Span::None;
+                let mut longest: Option<(I, O)> = None;
+
+                $(
+                    let mut try_input = input.clone();
+                    match self.$index.parse(&mut try_input) {
+                        Ok(output) => {
+                            if longest
+                                .as_ref()
+                                .is_none_or(|(longest, _)| longest.start() < try_input.start())
+                            {
+                                longest = Some((try_input, output));
+                            }
+                        }
+                        Err(err) if err.control_flow() == ControlFlow::Fatal => return Err(err),
+                        Err(err)
+                            if err.control_flow() == ControlFlow::Incomplete && input.is_partial() =>
+                        {
+                            return Err(err);
+                        }
+                        Err(err) => span = span.union(&err.to_span()),
+                    }
+                )+
+
+                if let Some((rest, output)) = longest {
+                    *input = rest;
+                    return Ok(output);
+                }
+
+                Err(alt_error(input, span))
+            }
+        }
+    };
+}
+
+/// Invoke [`impl_alt`] for every prefix of the parameter list with at least two parsers.
+macro_rules! impl_alt_tuples {
+    ($first:ident $first_index:tt $(, $parser:ident $index:tt)+) => {
+        impl_alt_tuples!(@ [$first $first_index] $($parser $index),+);
+    };
+    (@ [$($done:ident $done_index:tt)+] $next:ident $next_index:tt $(, $parser:ident $index:tt)*) => {
+        impl_alt!($($done $done_index,)+ $next $next_index);
+        impl_alt_tuples!(@ [$($done $done_index)+ $next $next_index] $($parser $index),*);
+    };
+    (@ [$($done:ident $done_index:tt)+]) => {};
+}
+
+impl_alt_tuples!(
+    P0 0, P1 1, P2 2, P3 3, P4 4, P5 5, P6 6, P7 7, P8 8, P9 9, P10 10, P11 11, P12 12, P13 13,
+    P14 14, P15 15
+);
+
+#[cfg(all(test, feature = "lang"))]
+mod tests {
+    use crate::{ControlFlow, Kind, Parser, Span, keyword, lang::TokenStream, next};
+
+    use super::{alt, alt_longest};
+
+    type TS<'a> = TokenStream<'a, Kind>;
+
+    #[test]
+    fn test_alt() {
+        let mut input = TS::from("bc");
+        assert_eq!(
+            alt((next(b'a'), next(b'b'), next(b'c'))).parse(&mut input),
+            Ok(TS::from("b"))
+        );
+        assert_eq!(input, TS::from((1, "c")));
+
+        assert_eq!(
+            alt((keyword("ab"), keyword("abcd"))).parse(&mut TS::from("abcd")),
+            Ok(TS::from("ab"))
+        );
+
+        assert_eq!(
+            alt((keyword("abc"), keyword("d"))).parse(&mut TS::from("abd")),
+            Err(Kind::Alt(ControlFlow::Recovable, Span::Range(0..3)))
+        );
+
+        assert_eq!(
+            alt((
+                next(b'a').then(next(b'b').fatal()).value(1),
+                next(b'a').value(2)
+            ))
+            .parse(&mut TS::from("ac")),
+            Err(Kind::Next(ControlFlow::Fatal, Span::Range(1..2)))
+        );
+    }
+
+    #[test]
+    fn test_alt_longest() {
+        let mut input = TS::from("abcd;");
+        assert_eq!(
+            alt_longest((keyword("ab"), keyword("abcd"), keyword("abc"))).parse(&mut input),
+            Ok(TS::from("abcd"))
+        );
+        assert_eq!(input, TS::from((4, ";")));
+
+        assert_eq!(
+            alt_longest((keyword("x"), keyword("y"))).parse(&mut TS::from("z")),
+            Err(Kind::Alt(ControlFlow::Recovable, Span::Range(0..1)))
+        );
+    }
+}
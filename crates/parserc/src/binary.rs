@@ -0,0 +1,481 @@
+//! Input type and parsers for binary file formats and wire protocols.
+
+use std::{fmt::Debug, iter::Enumerate, marker::PhantomData, slice::Iter};
+
+use memchr::memmem;
+
+use crate::{
+    ControlFlow, Kind, Length, ParseError, Span,
+    input::{AsBytes, Find, Input, StartWith},
+    parser::Parser,
+    take,
+};
+
+/// `Input` for raw bytes, unlike [`TokenStream`](crate::lang::TokenStream) it needs no valid UTF-8.
+#[allow(clippy::derived_hash_with_manual_eq)]
+#[derive(Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ByteStream<'a, E> {
+    /// offset in the whole byte stream.
+    pub offset: usize,
+    /// current segement in the whole byte stream.
+    pub value: &'a [u8],
+    /// Error type returns by this input.
+    _marker: PhantomData<E>,
+}
+
+impl<'a, E> Clone for ByteStream<'a, E> {
+    fn clone(&self) -> Self {
+        Self {
+            offset: self.offset,
+            value: self.value,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<'a, E> Debug for ByteStream<'a, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ByteStream")
+            .field("offset", &self.offset)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl<'a, E> PartialEq for ByteStream<'a, E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.offset == other.offset && self.value == other.value
+    }
+}
+
+impl<'a, E> From<&'a [u8]> for ByteStream<'a, E> {
+    fn from(value: &'a [u8]) -> Self {
+        ByteStream {
+            offset: 0,
+            value,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<'a, const N: usize, E> From<&'a [u8; N]> for ByteStream<'a, E> {
+    fn from(value: &'a [u8; N]) -> Self {
+        Self::from(value.as_slice())
+    }
+}
+
+impl<'a, E> From<(usize, &'a [u8])> for ByteStream<'a, E> {
+    fn from(value: (usize, &'a [u8])) -> Self {
+        ByteStream {
+            offset: value.0,
+            value: value.1,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<'a, E> Input for ByteStream<'a, E>
+where
+    E: ParseError,
+{
+    type Item = u8;
+
+    type Error = E;
+
+    type Iter = std::iter::Copied<Iter<'a, u8>>;
+
+    type IterIndices = Enumerate<Self::Iter>;
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.value.len()
+    }
+
+    #[inline]
+    fn split_to(&mut self, at: usize) -> Self {
+        let (first, last) = self.value.split_at(at);
+
+        self.value = last;
+        let offset = self.offset;
+        self.offset += at;
+
+        ByteStream {
+            offset,
+            value: first,
+            _marker: Default::default(),
+        }
+    }
+
+    #[inline]
+    fn split_off(&mut self, at: usize) -> Self {
+        let (first, last) = self.value.split_at(at);
+
+        self.value = first;
+
+        ByteStream {
+            offset: self.offset + at,
+            value: last,
+            _marker: Default::default(),
+        }
+    }
+
+    #[inline]
+    fn iter(&self) -> Self::Iter {
+        self.value.iter().copied()
+    }
+
+    #[inline]
+    fn iter_indices(&self) -> Self::IterIndices {
+        self.iter().enumerate()
+    }
+
+    #[inline]
+    fn start(&self) -> usize {
+        self.offset
+    }
+
+    #[inline]
+    fn end(&self) -> usize {
+        self.offset + self.value.len()
+    }
+}
+
+impl<'a, E> AsBytes for ByteStream<'a, E> {
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        self.value
+    }
+}
+
+impl<'a, E> StartWith<&[u8]> for ByteStream<'a, E> {
+    #[inline]
+    fn starts_with(&self, needle: &[u8]) -> Option<usize> {
+        if self.value.starts_with(needle) {
+            Some(needle.len())
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn needed(&self, needle: &[u8]) -> Option<usize> {
+        if needle.len() > self.value.len() && needle.starts_with(self.value) {
+            Some(needle.len() - self.value.len())
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, const N: usize, E> StartWith<&[u8; N]> for ByteStream<'a, E> {
+    #[inline]
+    fn starts_with(&self, needle: &[u8; N]) -> Option<usize> {
+        self.starts_with(needle.as_slice())
+    }
+
+    #[inline]
+    fn needed(&self, needle: &[u8; N]) -> Option<usize> {
+        self.needed(needle.as_slice())
+    }
+}
+
+impl<'a, E> Find<&[u8]> for ByteStream<'a, E> {
+    #[inline]
+    fn find(&self, needle: &[u8]) -> Option<usize> {
+        memmem::find(self.value, needle)
+    }
+}
+
+impl<'a, const N: usize, E> Find<&[u8; N]> for ByteStream<'a, E> {
+    #[inline]
+    fn find(&self, needle: &[u8; N]) -> Option<usize> {
+        memmem::find(self.value, needle)
+    }
+}
+
+macro_rules! numbers {
+    ($($ty: ty => $be: ident, $le: ident;)*) => {
+        $(
+            #[doc = concat!("Parse a big-endian `", stringify!($ty), "`.")]
+            #[inline]
+            pub fn $be<I>(input: &mut I) -> Result<$ty, I::Error>
+            where
+                I: Input<Item = u8> + AsBytes,
+            {
+                let bytes = take(size_of::<$ty>()).parse(input)?;
+                Ok(<$ty>::from_be_bytes(bytes.as_bytes().try_into().unwrap()))
+            }
+
+            #[doc = concat!("Parse a little-endian `", stringify!($ty), "`.")]
+            #[inline]
+            pub fn $le<I>(input: &mut I) -> Result<$ty, I::Error>
+            where
+                I: Input<Item = u8> + AsBytes,
+            {
+                let bytes = take(size_of::<$ty>()).parse(input)?;
+                Ok(<$ty>::from_le_bytes(bytes.as_bytes().try_into().unwrap()))
+            }
+        )*
+    };
+}
+
+numbers! {
+    u16 => be_u16, le_u16;
+    u32 => be_u32, le_u32;
+    u64 => be_u64, le_u64;
+    u128 => be_u128, le_u128;
+    i16 => be_i16, le_i16;
+    i32 => be_i32, le_i32;
+    i64 => be_i64, le_i64;
+    i128 => be_i128, le_i128;
+    f32 => be_f32, le_f32;
+    f64 => be_f64, le_f64;
+}
+
+/// Parse a `u8`.
+#[inline]
+pub fn u8<I>(input: &mut I) -> Result<u8, I::Error>
+where
+    I: Input<Item = u8> + AsBytes,
+{
+    Ok(take(1).parse(input)?.as_bytes()[0])
+}
+
+/// Parse an `i8`.
+#[inline]
+pub fn i8<I>(input: &mut I) -> Result<i8, I::Error>
+where
+    I: Input<Item = u8> + AsBytes,
+{
+    u8(input).map(|value| value as i8)
+}
+
+/// Parse the 7-bit groups of a LEB128 number, returns the value and the bit length.
+fn leb128<I>(input: &mut I, signed: bool) -> Result<(u64, u32), I::Error>
+where
+    I: Input<Item = u8> + AsBytes,
+{
+    let start = input.start();
+    let mut value = 0u64;
+    let mut shift = 0u32;
+
+    loop {
+        let byte = u8(input)?;
+        let group = (byte & 0x7f) as u64;
+
+        // only the lowest bit of the 10th group is in `u64`, the rest must be zero or its sign extension.
+        let overflow = match shift {
+            0..63 => false,
+            63 if signed => group != 0 && group != 0x7f,
+            63 => group > 1,
+            _ => true,
+        };
+
+        if overflow {
+            return Err(
+                Kind::Leb128(ControlFlow::Recovable, Span::Range(start..input.start())).into(),
+            );
+        }
+
+        value |= group << shift;
+        shift += 7;
+
+        if byte & 0x80 == 0 {
+            return Ok((value, shift));
+        }
+    }
+}
+
+/// Parse an unsigned LEB128 number, as used by `DWARF`, `wasm` and protobuf varints.
+///
+/// Returns a [`Kind::Leb128`] error if the value overflows `u64`.
+#[inline]
+pub fn uleb128<I>(input: &mut I) -> Result<u64, I::Error>
+where
+    I: Input<Item = u8> + AsBytes,
+{
+    leb128(input, false).map(|(value, _)| value)
+}
+
+/// Parse a signed LEB128 number.
+///
+/// Returns a [`Kind::Leb128`] error if the value overflows `i64`.
+#[inline]
+pub fn sleb128<I>(input: &mut I) -> Result<i64, I::Error>
+where
+    I: Input<Item = u8> + AsBytes,
+{
+    let (value, bits) = leb128(input, true)?;
+
+    // extends the sign bit of the last group.
+    if bits < u64::BITS && value & (1 << (bits - 1)) != 0 {
+        return Ok((value | (u64::MAX << bits)) as i64);
+    }
+
+    Ok(value as i64)
+}
+
+/// Parse a length by `length`, then returns a slice of that many items.
+///
+/// Returns a [`Kind::LengthPrefixed`] error if the length doesn't fit in `usize` or exceeds `max`,
+/// so a corrupted prefix can't make a partial input wait for gigabytes.
+#[inline]
+pub fn length_prefixed<I, P>(mut length: P, max: usize) -> impl Parser<I, Output = I>
+where
+    I: Input,
+    P: Parser<I>,
+    P::Output: TryInto<usize>,
+{
+    move |input: &mut I| {
+        let start = input.start();
+
+        let Some(len) = length
+            .parse(input)?
+            .try_into()
+            .ok()
+            .filter(|len| *len <= max)
+        else {
+            return Err(Kind::LengthPrefixed(
+                ControlFlow::Recovable,
+                Span::Range(start..input.start()),
+            )
+            .into());
+        };
+
+        take(len).parse(input)
+    }
+}
+
+/// Recognize a magic number, e.g. `b"\x7fELF"`.
+///
+/// Returns a [`Kind::Magic`] error if the input starts with other bytes.
+#[inline]
+pub fn magic<M, I>(magic: M) -> impl Parser<I, Output = I>
+where
+    I: Input + StartWith<M>,
+    M: Debug + Clone + Length,
+{
+    move |input: &mut I| {
+        if let Some(len) = input.starts_with(magic.clone()) {
+            return Ok(input.split_to(len));
+        }
+
+        if let Some(needed) = input
+            .is_partial()
+            .then(|| input.needed(magic.clone()))
+            .flatten()
+        {
+            return Err(Kind::Magic(ControlFlow::Incomplete, input.needed_at(needed)).into());
+        }
+
+        Err(Kind::Magic(ControlFlow::Recovable, input.to_span_at(magic.len())).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ControlFlow, Input, Kind, ParseError, Parser, Partial, Span};
+
+    use super::*;
+
+    type BS<'a> = ByteStream<'a, Kind>;
+
+    #[test]
+    fn test_numbers() {
+        let mut input =
+            BS::from(b"\x01\x02\x01\x02\xff\x00\x00\x80\x3f\x00\x00\x00\x00\x00\x00\xf0\xbf");
+
+        assert_eq!(be_u16(&mut input), Ok(0x0102));
+        assert_eq!(le_u16(&mut input), Ok(0x0201));
+        assert_eq!(i8(&mut input), Ok(-1));
+        assert_eq!(le_f32(&mut input), Ok(1.0));
+        assert_eq!(le_f64(&mut input), Ok(-1.0));
+        assert!(input.is_empty());
+
+        assert_eq!(
+            be_u32.parse(&mut BS::from((4, b"\x00\x01".as_slice()))),
+            Err(Kind::Take(ControlFlow::Incomplete, Span::RangeFrom(4..)))
+        );
+
+        let err = be_i64
+            .parse(&mut Partial::new(BS::from((4, b"\x00\x01".as_slice()))))
+            .unwrap_err();
+
+        assert_eq!(err.to_span(), Span::Range(6..12));
+        assert_eq!(err.needed(), Some(6));
+    }
+
+    #[test]
+    fn test_leb128() {
+        let mut input = BS::from(b"\xe5\x8e\x26\xc0\xbb\x78\x7f\x80\x7f");
+
+        assert_eq!(uleb128(&mut input), Ok(624485));
+        assert_eq!(sleb128(&mut input), Ok(-123456));
+        assert_eq!(sleb128(&mut input), Ok(-1));
+        assert_eq!(sleb128(&mut input), Ok(-128));
+
+        assert_eq!(
+            uleb128(&mut BS::from(b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\x01")),
+            Ok(u64::MAX)
+        );
+        assert_eq!(
+            uleb128(&mut BS::from(b"\xff\xff\xff\xff\xff\xff\xff\xff\xff\x02")),
+            Err(Kind::Leb128(ControlFlow::Recovable, Span::Range(0..10)))
+        );
+        assert_eq!(
+            sleb128(&mut BS::from(b"\x80\x80\x80\x80\x80\x80\x80\x80\x80\x7f")),
+            Ok(i64::MIN)
+        );
+        assert_eq!(
+            uleb128(&mut BS::from(b"\x80")),
+            Err(Kind::Take(ControlFlow::Incomplete, Span::RangeFrom(1..)))
+        );
+    }
+
+    #[test]
+    fn test_length_prefixed() {
+        let mut input = BS::from(b"\x00\x03abcd");
+
+        assert_eq!(
+            length_prefixed(be_u16, 3).parse(&mut input),
+            Ok(BS::from((2, b"abc".as_slice())))
+        );
+        assert_eq!(
+            length_prefixed(i8, 16).parse(&mut BS::from(b"\xff")),
+            Err(Kind::LengthPrefixed(
+                ControlFlow::Recovable,
+                Span::Range(0..1)
+            ))
+        );
+        assert_eq!(
+            length_prefixed(be_u16, 2).parse(&mut BS::from(b"\x00\x03abc")),
+            Err(Kind::LengthPrefixed(
+                ControlFlow::Recovable,
+                Span::Range(0..2)
+            ))
+        );
+        assert_eq!(
+            length_prefixed(be_u32, 1024).parse(&mut Partial::new(BS::from(b"\xff\xff\xff\xff"))),
+            Err(Kind::LengthPrefixed(
+                ControlFlow::Recovable,
+                Span::Range(0..4)
+            ))
+        );
+
+        assert_eq!(
+            magic(b"\x7fELF").parse(&mut BS::from(b"\x7fELF\x02")),
+            Ok(BS::from(b"\x7fELF"))
+        );
+        assert_eq!(
+            magic(b"\x7fELF").parse(&mut BS::from(b"MZ\x90\x00\x03")),
+            Err(Kind::Magic(ControlFlow::Recovable, Span::Range(0..4)))
+        );
+        assert_eq!(
+            magic(b"\x7fELF")
+                .parse(&mut Partial::new(BS::from(b"\x7fE")))
+                .map_err(|err| err.needed()),
+            Err(Some(2))
+        );
+    }
+}
@@ -0,0 +1,458 @@
+//! Bit-level input and parsers for packed binary formats.
+
+use std::{fmt::Debug, fmt::Display, iter::Enumerate};
+
+use crate::{AsBytes, ControlFlow, Input, Kind, Parser, Span, take};
+
+/// The order bits are read from a byte.
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BitOrder {
+    /// The most significant bit first, the first bit read is the most significant bit of a value.
+    #[default]
+    Msb,
+    /// The least significant bit first, the first bit read is the least significant bit of a value,
+    /// as used by `deflate`.
+    Lsb,
+}
+
+/// An `Input` adapter reads a byte `Input` bit by bit.
+///
+/// Positions are global bit offsets, `byte offset * 8 + bit`, so combinators and progress checks work as usual;
+/// use [`BitInput::byte_span`] to map a span back to byte offsets for diagnostics.
+/// The [`MemoTable`](crate::MemoTable) of the byte input is not shared, its keys are byte offsets.
+#[derive(Clone)]
+pub struct BitInput<I> {
+    /// bytes covering the bit range.
+    input: I,
+    /// global bit offset of the first bit.
+    start: usize,
+    /// global bit offset past the last bit.
+    end: usize,
+    order: BitOrder,
+}
+
+impl<I> BitInput<I>
+where
+    I: Input<Item = u8> + AsBytes + Clone,
+{
+    /// Read `input` with the most significant bit first.
+    #[inline]
+    pub fn new(input: I) -> Self {
+        Self::with_order(input, BitOrder::Msb)
+    }
+
+    /// Read `input` with bits in `order`.
+    #[inline]
+    pub fn with_order(input: I, order: BitOrder) -> Self {
+        Self {
+            start: input.start() * 8,
+            end: input.end() * 8,
+            input,
+            order,
+        }
+    }
+
+    /// Returns the bit order.
+    #[inline]
+    pub fn order(&self) -> BitOrder {
+        self.order
+    }
+
+    /// Returns the bit offset in the current byte.
+    #[inline]
+    pub fn bit_offset(&self) -> usize {
+        self.start % 8
+    }
+
+    /// Returns true if the position is on a byte boundary.
+    #[inline]
+    pub fn is_aligned(&self) -> bool {
+        self.bit_offset() == 0
+    }
+
+    /// Consumes this adapter and returns the byte input from the current byte, including a partially read one.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    /// Map a span of bit offsets to the bytes it touches.
+    pub fn byte_span(span: &Span) -> Span {
+        match span {
+            Span::None => Span::None,
+            Span::Range(range) => Span::Range(range.start / 8..range.end.div_ceil(8)),
+            Span::RangeFrom(range_from) => Span::RangeFrom(range_from.start / 8..),
+            Span::RangeTo(range_to) => Span::RangeTo(..range_to.end.div_ceil(8)),
+            Span::RangeFull => Span::RangeFull,
+        }
+    }
+
+    /// Returns the value of bits in this input.
+    fn value(&self) -> u64 {
+        self.iter()
+            .enumerate()
+            .fold(0, |value, (i, bit)| match self.order {
+                BitOrder::Msb => value << 1 | bit as u64,
+                BitOrder::Lsb => value | (bit as u64) << i,
+            })
+    }
+}
+
+impl<I> Debug for BitInput<I>
+where
+    I: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BitInput")
+            .field("input", &self.input)
+            .field("start", &self.start)
+            .field("end", &self.end)
+            .finish()
+    }
+}
+
+impl<I> PartialEq for BitInput<I>
+where
+    I: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.input == other.input && self.start == other.start && self.end == other.end
+    }
+}
+
+/// Iterator returns by [`BitInput::iter`](Input::iter).
+#[derive(Debug, Clone)]
+pub struct BitIter<I> {
+    input: I,
+    start: usize,
+    end: usize,
+    order: BitOrder,
+}
+
+impl<I> Iterator for BitIter<I>
+where
+    I: Input<Item = u8> + AsBytes,
+{
+    type Item = bool;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            return None;
+        }
+
+        let byte = self.input.as_bytes()[self.start / 8 - self.input.start()];
+        let bit = match self.order {
+            BitOrder::Msb => 7 - self.start % 8,
+            BitOrder::Lsb => self.start % 8,
+        };
+
+        self.start += 1;
+
+        Some(byte >> bit & 1 == 1)
+    }
+}
+
+impl<I> Input for BitInput<I>
+where
+    I: Input<Item = u8> + AsBytes + Clone,
+{
+    type Item = bool;
+
+    type Error = I::Error;
+
+    type Iter = BitIter<I>;
+
+    type IterIndices = Enumerate<Self::Iter>;
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    fn split_to(&mut self, at: usize) -> Self {
+        let mid = self.start + at;
+
+        let mut first = self.clone();
+        first.input.split_off(mid.div_ceil(8) - self.input.start());
+        first.end = mid;
+
+        self.input.split_to(mid / 8 - self.input.start());
+        self.start = mid;
+
+        first
+    }
+
+    fn split_off(&mut self, at: usize) -> Self {
+        let mid = self.start + at;
+
+        let mut last = self.clone();
+        last.input.split_to(mid / 8 - self.input.start());
+        last.start = mid;
+
+        self.input.split_off(mid.div_ceil(8) - self.input.start());
+        self.end = mid;
+
+        last
+    }
+
+    #[inline]
+    fn iter(&self) -> Self::Iter {
+        BitIter {
+            input: self.input.clone(),
+            start: self.start,
+            end: self.end,
+            order: self.order,
+        }
+    }
+
+    #[inline]
+    fn iter_indices(&self) -> Self::IterIndices {
+        self.iter().enumerate()
+    }
+
+    #[inline]
+    fn start(&self) -> usize {
+        self.start
+    }
+
+    #[inline]
+    fn end(&self) -> usize {
+        self.end
+    }
+
+    #[inline]
+    fn recover(&self, error: Self::Error) -> Result<(), Self::Error> {
+        self.input.recover(error)
+    }
+
+    #[inline]
+    fn report_expected(&self, name: impl Display) {
+        self.input.report_expected(name);
+    }
+
+    #[inline]
+    fn is_partial(&self) -> bool {
+        self.input.is_partial()
+    }
+}
+
+/// A parser returns the value of the next `n` bits, read in the input [`BitOrder`].
+///
+/// # Panics
+///
+/// Panics if `n` is greater than 64.
+#[inline]
+pub fn take_bits<I>(n: usize) -> impl Parser<BitInput<I>, Output = u64>
+where
+    I: Input<Item = u8> + AsBytes + Clone,
+{
+    assert!(n <= 64, "take_bits: can't read {} bits into `u64`", n);
+
+    move |input: &mut BitInput<I>| take(n).parse(input).map(|bits| bits.value())
+}
+
+/// A parser returns the value of the next `n` bits as a two's complement signed number.
+///
+/// # Panics
+///
+/// Panics if `n` is greater than 64.
+#[inline]
+pub fn take_signed_bits<I>(n: usize) -> impl Parser<BitInput<I>, Output = i64>
+where
+    I: Input<Item = u8> + AsBytes + Clone,
+{
+    take_bits(n).map(move |value| {
+        // extends the sign bit.
+        if n > 0 && n < 64 && value >> (n - 1) & 1 == 1 {
+            (value | u64::MAX << n) as i64
+        } else {
+            value as i64
+        }
+    })
+}
+
+/// Parse one bit as a `bool`.
+#[inline]
+pub fn bool<I>(input: &mut BitInput<I>) -> Result<bool, I::Error>
+where
+    I: Input<Item = u8> + AsBytes + Clone,
+{
+    take_bits(1).map(|bit| bit == 1).parse(input)
+}
+
+/// Skip the padding bits to the next byte boundary, returns the skipped bits.
+#[inline]
+pub fn align<I>(input: &mut BitInput<I>) -> Result<BitInput<I>, I::Error>
+where
+    I: Input<Item = u8> + AsBytes + Clone,
+{
+    let padding = (8 - input.bit_offset()) % 8;
+
+    take(padding).parse(input)
+}
+
+macro_rules! unaligned {
+    ($($ty: ty => $name: ident;)*) => {
+        $(
+            #[doc = concat!("Parse an `", stringify!($ty), "` from the next `", stringify!($ty), "::BITS` bits at any position, in the input [`BitOrder`].")]
+            #[inline]
+            pub fn $name<I>(input: &mut BitInput<I>) -> Result<$ty, I::Error>
+            where
+                I: Input<Item = u8> + AsBytes + Clone,
+            {
+                take_bits(<$ty>::BITS as usize).map(|value| value as $ty).parse(input)
+            }
+        )*
+    };
+}
+
+unaligned! {
+    u8 => u8;
+    u16 => u16;
+    u32 => u32;
+    u64 => u64;
+    i8 => i8;
+    i16 => i16;
+    i32 => i32;
+    i64 => i64;
+}
+
+macro_rules! aligned {
+    ($($ty: ty => $be: ident, $le: ident;)*) => {
+        $(
+            #[doc = concat!("Parse a big-endian `", stringify!($ty), "` on a byte boundary.")]
+            ///
+            /// Returns a [`Kind::Unaligned`] error if the position is not on a byte boundary.
+            #[inline]
+            pub fn $be<I>(input: &mut BitInput<I>) -> Result<$ty, I::Error>
+            where
+                I: Input<Item = u8> + AsBytes + Clone,
+            {
+                let bytes = aligned_bytes(input, size_of::<$ty>())?;
+                Ok(<$ty>::from_be_bytes(bytes.input.as_bytes().try_into().unwrap()))
+            }
+
+            #[doc = concat!("Parse a little-endian `", stringify!($ty), "` on a byte boundary.")]
+            ///
+            /// Returns a [`Kind::Unaligned`] error if the position is not on a byte boundary.
+            #[inline]
+            pub fn $le<I>(input: &mut BitInput<I>) -> Result<$ty, I::Error>
+            where
+                I: Input<Item = u8> + AsBytes + Clone,
+            {
+                let bytes = aligned_bytes(input, size_of::<$ty>())?;
+                Ok(<$ty>::from_le_bytes(bytes.input.as_bytes().try_into().unwrap()))
+            }
+        )*
+    };
+}
+
+aligned! {
+    u16 => be_u16, le_u16;
+    u32 => be_u32, le_u32;
+    u64 => be_u64, le_u64;
+    i16 => be_i16, le_i16;
+    i32 => be_i32, le_i32;
+    i64 => be_i64, le_i64;
+}
+
+/// Returns the next `len` whole bytes, the position must be on a byte boundary.
+fn aligned_bytes<I>(input: &mut BitInput<I>, len: usize) -> Result<BitInput<I>, I::Error>
+where
+    I: Input<Item = u8> + AsBytes + Clone,
+{
+    if !input.is_aligned() {
+        return Err(Kind::Unaligned(
+            ControlFlow::Recovable,
+            input.to_span_at(8 - input.bit_offset()),
+        )
+        .into());
+    }
+
+    take(len * 8).parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ControlFlow, Input, Kind, ParseError, Parser, Partial, Span, binary::ByteStream};
+
+    use super::*;
+
+    type BS<'a> = ByteStream<'a, Kind>;
+
+    #[test]
+    fn test_bits() {
+        let mut input = BitInput::new(BS::from((2, b"\xa5\x0f\x12\x34".as_slice())));
+
+        assert_eq!(input.start(), 16);
+        assert_eq!(take_bits(3).parse(&mut input), Ok(0b101));
+        assert_eq!(bool(&mut input), Ok(false));
+        assert_eq!(take_signed_bits(4).parse(&mut input), Ok(0b0101));
+        assert_eq!(take_signed_bits(4).parse(&mut input), Ok(0));
+        assert_eq!(input.bit_offset(), 4);
+
+        assert_eq!(
+            be_u16(&mut input),
+            Err(Kind::Unaligned(ControlFlow::Recovable, Span::Range(28..32)))
+        );
+        assert_eq!(take_signed_bits(2).parse(&mut input), Ok(-1));
+        assert_eq!(align(&mut input).map(|padding| padding.len()), Ok(2));
+        assert_eq!(be_u16(&mut input), Ok(0x1234));
+        assert!(input.is_empty());
+
+        let err = u8(&mut input).unwrap_err();
+        assert_eq!(
+            err,
+            Kind::Take(ControlFlow::Incomplete, Span::RangeFrom(48..))
+        );
+        assert_eq!(
+            BitInput::<BS<'_>>::byte_span(&err.to_span()),
+            Span::RangeFrom(6..)
+        );
+    }
+
+    #[test]
+    fn test_unaligned() {
+        let mut input = BitInput::new(BS::from(b"\xf1\x23\x40"));
+
+        assert_eq!(take_bits(4).parse(&mut input), Ok(0xf));
+        assert_eq!(u16(&mut input), Ok(0x1234));
+        assert_eq!(
+            BitInput::<BS<'_>>::byte_span(&input.to_span()),
+            Span::Range(2..3)
+        );
+
+        // `deflate` block header: BFINAL = 1, BTYPE = 0b10.
+        let mut input = BitInput::with_order(BS::from(b"\x05"), BitOrder::Lsb);
+
+        assert_eq!(bool(&mut input), Ok(true));
+        assert_eq!(take_bits(2).parse(&mut input), Ok(0b10));
+        assert_eq!(
+            take_bits(1)
+                .many0()
+                .parse(&mut input)
+                .map(|bits| bits.len()),
+            Ok(5)
+        );
+    }
+
+    #[test]
+    fn test_partial() {
+        let mut input = BitInput::new(Partial::new(BS::from(b"\xab")));
+
+        assert_eq!(take_bits(4).parse(&mut input), Ok(0xa));
+
+        // the missing region is in bits, like every other span.
+        let err = take_bits(12).parse(&mut input).unwrap_err();
+        assert_eq!(err, Kind::Take(ControlFlow::Incomplete, Span::Range(8..16)));
+        assert_eq!(err.needed(), Some(8));
+
+        // rounded up to whole bytes.
+        let err = take_bits(13).parse(&mut input).unwrap_err();
+        assert_eq!(
+            BitInput::<Partial<BS<'_>>>::byte_span(&err.to_span()),
+            Span::Range(1..3)
+        );
+    }
+}
@@ -0,0 +1,231 @@
+//! Type-erased and recursive parsers.
+
+use std::{
+    cell::RefCell,
+    rc::{Rc, Weak},
+};
+
+use crate::{input::Input, parser::Parser};
+
+/// A type-erased [`Parser`], created by [`Parser::into_boxed`].
+///
+/// Parsers of different types can be stored together as long as they share input and output types.
+pub struct BoxedParser<'a, I, O>(Box<dyn Parser<I, Output = O> + 'a>)
+where
+    I: Input;
+
+impl<'a, I, O> BoxedParser<'a, I, O>
+where
+    I: Input,
+{
+    /// Create a new `BoxedParser` from any parser.
+    #[inline]
+    pub fn new<P>(parser: P) -> Self
+    where
+        P: Parser<I, Output = O> + 'a,
+    {
+        Self(Box::new(parser))
+    }
+}
+
+impl<'a, I, O> Parser<I> for BoxedParser<'a, I, O>
+where
+    I: Input,
+{
+    type Output = O;
+
+    #[inline]
+    fn parse(&mut self, input: &mut I) -> Result<Self::Output, I::Error> {
+        self.0.parse(input)
+    }
+}
+
+/// Func that builds the grammar of a [`Recursive`] parser from its own handle.
+type Definition<'a, I, O> = dyn Fn(Recursive<'a, I, O>) -> BoxedParser<'a, I, O> + 'a;
+
+/// The grammar definition and the parsers built from it, shared by the handles of a [`Recursive`] parser.
+struct Grammar<'a, I, O>
+where
+    I: Input,
+{
+    definition: Box<Definition<'a, I, O>>,
+    /// Built parsers that are not running, a nested call takes its own one.
+    idle: RefCell<Vec<BoxedParser<'a, I, O>>>,
+}
+
+impl<'a, I, O> Grammar<'a, I, O>
+where
+    I: Input,
+{
+    fn parse(self: Rc<Self>, input: &mut I) -> Result<O, I::Error> {
+        let built = self.idle.borrow_mut().pop();
+
+        // the grammar holds a weak handle of itself, so it doesn't keep itself alive.
+        let mut parser = built
+            .unwrap_or_else(|| (self.definition)(Recursive(Handle::Weak(Rc::downgrade(&self)))));
+
+        let output = parser.parse(input);
+
+        self.idle.borrow_mut().push(parser);
+
+        output
+    }
+}
+
+enum Handle<'a, I, O>
+where
+    I: Input,
+{
+    Strong(Rc<Grammar<'a, I, O>>),
+    Weak(Weak<Grammar<'a, I, O>>),
+}
+
+/// A parser that can refer to itself, created by [`recursive`].
+pub struct Recursive<'a, I, O>(Handle<'a, I, O>)
+where
+    I: Input;
+
+impl<'a, I, O> Clone for Recursive<'a, I, O>
+where
+    I: Input,
+{
+    fn clone(&self) -> Self {
+        match &self.0 {
+            Handle::Strong(grammar) => Self(Handle::Strong(grammar.clone())),
+            Handle::Weak(grammar) => Self(Handle::Weak(grammar.clone())),
+        }
+    }
+}
+
+impl<'a, I, O> Parser<I> for Recursive<'a, I, O>
+where
+    I: Input,
+{
+    type Output = O;
+
+    #[inline]
+    fn parse(&mut self, input: &mut I) -> Result<Self::Output, I::Error> {
+        let grammar = match &self.0 {
+            Handle::Strong(grammar) => grammar.clone(),
+            Handle::Weak(grammar) => grammar
+                .upgrade()
+                .expect("the handle of a `recursive` parser outlives the parser"),
+        };
+
+        grammar.parse(input)
+    }
+}
+
+/// Create a parser that can refer to itself.
+///
+/// The func `F` receives a handle of the parser being defined and returns the grammar built from it.
+/// The built grammar is kept and reused, `F` is only called again when the parser
+/// is nested deeper than before, as every running nesting level needs its own parser.
+///
+/// ```
+/// use parserc::{Kind, Parser, lang::TokenStream, next, recursive};
+///
+/// type TS<'a> = TokenStream<'a, Kind>;
+///
+/// // the nesting depth of balanced parentheses.
+/// let mut depth = recursive(|this| {
+///     this.delimited(next(b'('), next(b')').fatal())
+///         .map(|depth| depth + 1)
+///         .or(|_: &mut TS<'_>| Ok(0))
+/// });
+///
+/// assert_eq!(depth.parse(&mut TS::from("((()))")), Ok(3));
+/// ```
+#[inline]
+pub fn recursive<'a, I, O, F, P>(f: F) -> Recursive<'a, I, O>
+where
+    I: Input,
+    F: Fn(Recursive<'a, I, O>) -> P + 'a,
+    P: Parser<I, Output = O> + 'a,
+{
+    Recursive(Handle::Strong(Rc::new(Grammar {
+        definition: Box::new(move |this| BoxedParser::new(f(this))),
+        idle: RefCell::new(vec![]),
+    })))
+}
+
+#[cfg(all(test, feature = "lang"))]
+mod tests {
+    use crate::{ControlFlow, Kind, Parser, Span, keyword, lang::TokenStream, next};
+
+    use std::{cell::Cell, rc::Rc};
+
+    use super::{BoxedParser, Handle, recursive};
+
+    type TS<'a> = TokenStream<'a, Kind>;
+
+    #[test]
+    fn test_boxed() {
+        let keywords = ["fn", "let", "struct"];
+
+        let mut parsers: Vec<BoxedParser<'_, TS<'_>, usize>> = keywords
+            .iter()
+            .enumerate()
+            .map(|(index, kw)| keyword(*kw).value(index).into_boxed())
+            .collect();
+
+        let mut input = TS::from("letfnstruct");
+
+        for expect in [1, 0, 2] {
+            let output = parsers
+                .iter_mut()
+                .find_map(|parser| parser.by_ref().ok().parse(&mut input).unwrap());
+
+            assert_eq!(output, Some(expect));
+        }
+    }
+
+    #[test]
+    fn test_recursive() {
+        let mut depth = recursive(|this| {
+            this.delimited(next(b'('), next(b')').fatal())
+                .map(|depth| depth + 1)
+                .or(|_: &mut TS<'_>| Ok(0))
+        });
+
+        assert_eq!(depth.parse(&mut TS::from("((()))")), Ok(3));
+        assert_eq!(depth.parse(&mut TS::from("")), Ok(0));
+        assert_eq!(
+            depth.parse(&mut TS::from("(()")),
+            Err(Kind::Next(ControlFlow::Fatal, Span::RangeFrom(3..)))
+        );
+    }
+
+    #[test]
+    fn test_recursive_built_once() {
+        let built = Rc::new(Cell::new(0));
+
+        let mut depth = recursive({
+            let built = built.clone();
+            move |this| {
+                built.set(built.get() + 1);
+
+                this.delimited(next(b'('), next(b')'))
+                    .map(|depth| depth + 1)
+                    .or(|_: &mut TS<'_>| Ok(0))
+            }
+        });
+
+        // one parser for every running nesting level.
+        assert_eq!(depth.parse(&mut TS::from("((()))")), Ok(3));
+        assert_eq!(built.get(), 4);
+
+        assert_eq!(depth.parse(&mut TS::from("(())")), Ok(2));
+        assert_eq!(depth.parse(&mut TS::from("((()))")), Ok(3));
+        assert_eq!(built.get(), 4);
+
+        // the grammar is freed with the last strong handle.
+        let grammar = match &depth.0 {
+            Handle::Strong(grammar) => Rc::downgrade(grammar),
+            Handle::Weak(grammar) => grammar.clone(),
+        };
+
+        drop(depth);
+        assert!(grammar.upgrade().is_none());
+    }
+}
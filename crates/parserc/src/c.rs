@@ -26,6 +26,8 @@ where
                 Span::Range(input.start()..input.start() + 1),
             ))
             .into())
+        } else if input.is_partial() {
+            Err((Kind::Next(ControlFlow::Incomplete, input.needed_at(item.len()))).into())
         } else {
             Err((Kind::Next(ControlFlow::Incomplete, Span::RangeFrom(input.start()..))).into())
         }
@@ -34,10 +36,10 @@ where
 
 /// A parser match next item by `F`, otherwise raise an error.
 #[inline]
-pub fn next_if<I, F>(f: F) -> impl Parser<I, Output = I>
+pub fn next_if<I, F>(mut f: F) -> impl Parser<I, Output = I>
 where
     I: Input,
-    F: FnOnce(I::Item) -> bool,
+    F: FnMut(I::Item) -> bool,
 {
     move |input: &mut I| {
         if let Some(next) = input.iter().next() {
@@ -46,12 +48,44 @@ where
             }
 
             Err((Kind::NextIf(ControlFlow::Recovable, input.to_span())).into())
+        } else if input.is_partial() {
+            Err((Kind::NextIf(ControlFlow::Incomplete, input.needed_at(1))).into())
         } else {
             Err((Kind::NextIf(ControlFlow::Incomplete, input.to_span())).into())
         }
     }
 }
 
+/// A parser returns the next `n` items.
+///
+/// If the input has fewer items, returns a [`ControlFlow::Incomplete`] error.
+#[inline]
+pub fn take<I>(n: usize) -> impl Parser<I, Output = I>
+where
+    I: Input,
+{
+    move |input: &mut I| {
+        let mut iter = input.iter();
+        let mut offset = 0;
+
+        for count in 0..n {
+            let Some(next) = iter.next() else {
+                let span = if input.is_partial() {
+                    input.needed_at(n - count)
+                } else {
+                    Span::RangeFrom(input.start()..)
+                };
+
+                return Err(Kind::Take(ControlFlow::Incomplete, span).into());
+            };
+
+            offset += next.len();
+        }
+
+        Ok(input.split_to(offset))
+    }
+}
+
 /// Recogonize a keyword
 #[inline]
 pub fn keyword<KW, I>(keyword: KW) -> impl Parser<I, Output = I>
@@ -62,6 +96,12 @@ where
     move |input: &mut I| {
         if let Some(len) = input.starts_with(keyword.clone()) {
             Ok(input.split_to(len))
+        } else if let Some(needed) = input
+            .is_partial()
+            .then(|| input.needed(keyword.clone()))
+            .flatten()
+        {
+            Err((Kind::Keyword(ControlFlow::Incomplete, input.needed_at(needed))).into())
         } else {
             Err((Kind::Keyword(
                 ControlFlow::Recovable,
@@ -84,6 +124,8 @@ where
     move |input: &mut I| {
         if let Some(offset) = input.find(keyword.clone()) {
             Ok(input.split_to(offset))
+        } else if input.is_partial() {
+            Err(Kind::TakeUntil(ControlFlow::Incomplete, input.needed_at(0)).into())
         } else {
             Err(Kind::TakeUntil(
                 ControlFlow::Recovable,
@@ -96,7 +138,7 @@ where
 
 /// Returns the longest input slice (if any) that the predicate `F` returns true.
 ///
-/// This parser will never returns an error.
+/// This parser never returns an error, unless it reaches the end of a [`partial`](Input::is_partial) input.
 #[inline]
 pub fn take_while<I, F>(mut cond: F) -> impl Parser<I, Output = I>
 where
@@ -104,18 +146,18 @@ where
     F: FnMut(I::Item) -> bool,
 {
     move |input: &mut I| {
-        let mut iter = input.iter();
+        let iter = input.iter();
         let mut offset = 0;
-        loop {
-            if let Some(next) = iter.next() {
-                if !(cond)(next) {
-                    break;
-                }
-
-                offset += next.len();
-            } else {
+        for next in iter {
+            if !(cond)(next) {
                 break;
             }
+
+            offset += next.len();
+        }
+
+        if offset == input.len() && input.is_partial() {
+            return Err(Kind::TakeWhile(ControlFlow::Incomplete, input.needed_at(0)).into());
         }
 
         Ok(input.split_to(offset))
@@ -124,7 +166,7 @@ where
 
 /// Returns the longest input slice of length `n` (if any) that the predicate `F` returns true.
 ///
-/// This parser will never returns an error.
+/// This parser never returns an error, unless it reaches the end of a [`partial`](Input::is_partial) input.
 #[inline]
 pub fn take_while_range_to<I, F>(n: usize, mut cond: F) -> impl Parser<I, Output = I>
 where
@@ -146,6 +188,10 @@ where
             }
         }
 
+        if offset < n && offset == input.len() && input.is_partial() {
+            return Err(Kind::TakeWhile(ControlFlow::Incomplete, input.needed_at(0)).into());
+        }
+
         Ok(input.split_to(offset))
     }
 }
@@ -160,18 +206,22 @@ where
     F: FnMut(I::Item) -> bool,
 {
     move |input: &mut I| {
-        let mut iter = input.iter();
+        let iter = input.iter();
         let mut offset = 0;
-        loop {
-            if let Some(next) = iter.next() {
-                if !(cond)(next) {
-                    break;
-                }
-
-                offset += next.len();
-            } else {
+        for next in iter {
+            if !(cond)(next) {
                 break;
             }
+
+            offset += next.len();
+        }
+
+        if offset == input.len() && input.is_partial() {
+            return Err(Kind::TakeWhileFrom(
+                ControlFlow::Incomplete,
+                input.needed_at(n.saturating_sub(offset)),
+            )
+            .into());
         }
 
         if offset < n {
@@ -210,6 +260,14 @@ where
             }
         }
 
+        if offset < range.end && offset == input.len() && input.is_partial() {
+            return Err(Kind::TakeWhileRange(
+                ControlFlow::Incomplete,
+                input.needed_at(range.start.saturating_sub(offset)),
+            )
+            .into());
+        }
+
         if offset < range.start {
             return Err(Kind::TakeWhileRange(
                 ControlFlow::Recovable,
@@ -0,0 +1,401 @@
+//! Render parsing errors as source snippets with labels.
+
+use std::fmt::{Display, Write};
+
+use crate::{ColumnUnit, LineIndex, ParseError, SourceFile, Span};
+
+/// Severity of a [`Diagnostic`].
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+    Help,
+}
+
+impl Severity {
+    /// ANSI colour of this severity.
+    fn color(&self) -> &'static str {
+        match self {
+            Severity::Error => "\x1b[1;31m",
+            Severity::Warning => "\x1b[1;33m",
+            Severity::Note => "\x1b[1;32m",
+            Severity::Help => "\x1b[1;36m",
+        }
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+            Severity::Help => write!(f, "help"),
+        }
+    }
+}
+
+/// A message attached to a region of the source code.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Label {
+    /// The labelled region.
+    pub span: Span,
+    /// Message shown under the region, may be empty.
+    pub message: String,
+}
+
+/// A diagnostic with a primary span, secondary labels and notes.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Diagnostic {
+    /// Severity of this diagnostic.
+    pub severity: Severity,
+    /// Headline message.
+    pub message: String,
+    /// The primary label.
+    pub primary: Label,
+    /// Secondary labels.
+    pub labels: Vec<Label>,
+    /// Notes shown after the snippet.
+    pub notes: Vec<String>,
+}
+
+impl Diagnostic {
+    /// Create a new diagnostic, with an empty primary label.
+    pub fn new(severity: Severity, message: impl Into<String>, span: Span) -> Self {
+        Self {
+            severity,
+            message: message.into(),
+            primary: Label {
+                span,
+                message: String::new(),
+            },
+            labels: vec![],
+            notes: vec![],
+        }
+    }
+
+    /// Create a new error diagnostic.
+    #[inline]
+    pub fn error(message: impl Into<String>, span: Span) -> Self {
+        Self::new(Severity::Error, message, span)
+    }
+
+    /// Create an error diagnostic from a parsing error.
+    ///
+    /// The message is the innermost error, its context frames become notes, e.g. `in function body`.
+    pub fn from_error<E>(err: &E) -> Self
+    where
+        E: ParseError + Display,
+    {
+        let mut diagnostic = Self::error(err.root_message(), err.to_span());
+
+        for context in err.contexts() {
+            diagnostic = diagnostic.with_note(format!("in {}", context));
+        }
+
+        diagnostic
+    }
+
+    /// Set the message of the primary label.
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.primary.message = message.into();
+        self
+    }
+
+    /// Append a secondary label.
+    pub fn with_label(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            span,
+            message: message.into(),
+        });
+        self
+    }
+
+    /// Append a note.
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.notes.push(note.into());
+        self
+    }
+}
+
+/// Renders [`Diagnostic`]s as terminal snippets.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Renderer {
+    colored: bool,
+}
+
+const RESET: &str = "\x1b[0m";
+const GUTTER: &str = "\x1b[1;34m";
+
+impl Renderer {
+    /// Create a renderer without ANSI colours, the output is stable for snapshot tests.
+    #[inline]
+    pub fn plain() -> Self {
+        Self { colored: false }
+    }
+
+    /// Create a renderer with ANSI colours.
+    #[inline]
+    pub fn colored() -> Self {
+        Self { colored: true }
+    }
+
+    /// Render `diagnostic` on the `source` code of file `path`, spans are offsets in `source`.
+    #[inline]
+    pub fn render(&self, diagnostic: &Diagnostic, path: &str, source: &str) -> String {
+        self.render_at(diagnostic, path, source, 0)
+    }
+
+    /// Render `diagnostic` on a [`SourceFile`], spans are global offsets of its `SourceMap`.
+    #[inline]
+    pub fn render_file(&self, diagnostic: &Diagnostic, file: &SourceFile) -> String {
+        let path = file.path().display().to_string();
+        self.render_at(diagnostic, &path, file.source(), file.range().start)
+    }
+
+    fn paint(&self, color: &str, text: impl Display) -> String {
+        if self.colored {
+            format!("{}{}{}", color, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn render_at(&self, diagnostic: &Diagnostic, path: &str, source: &str, base: usize) -> String {
+        let index = LineIndex::new(source);
+        let color = diagnostic.severity.color();
+
+        // (is_primary, local range, message)
+        let labels = [(true, &diagnostic.primary)]
+            .into_iter()
+            .chain(diagnostic.labels.iter().map(|label| (false, label)))
+            .filter_map(|(primary, label)| {
+                let range = local_range(&label.span, base, source.len())?;
+                Some((primary, range, label.message.as_str()))
+            })
+            .collect::<Vec<_>>();
+
+        let mut lines = labels
+            .iter()
+            .flat_map(|(_, range, _)| {
+                let start = index.position(range.start, ColumnUnit::Char).unwrap().line;
+                let end = index.position(range.end, ColumnUnit::Char).unwrap().line;
+                start..=end
+            })
+            .collect::<Vec<_>>();
+
+        lines.sort();
+        lines.dedup();
+
+        let width = lines.last().map_or(1, |line| (line + 1).to_string().len());
+        let gutter = self.paint(GUTTER, format!("{} |", " ".repeat(width)));
+
+        let mut output = String::new();
+
+        _ = writeln!(
+            output,
+            "{}{}",
+            self.paint(color, diagnostic.severity),
+            self.paint("\x1b[1m", format!(": {}", diagnostic.message))
+        );
+
+        let location = labels
+            .first()
+            .filter(|(primary, _, _)| *primary)
+            .map(|(_, range, _)| index.position(range.start, ColumnUnit::Char).unwrap());
+
+        let location = match location {
+            Some(position) => format!("{}:{}", path, position),
+            None => path.to_string(),
+        };
+
+        _ = writeln!(
+            output,
+            "{}{} {}",
+            " ".repeat(width),
+            self.paint(GUTTER, "-->"),
+            location
+        );
+
+        if !lines.is_empty() {
+            _ = writeln!(output, "{}", gutter);
+        }
+
+        for (i, line) in lines.iter().enumerate() {
+            if i > 0 && lines[i - 1] + 1 != *line {
+                _ = writeln!(output, "{}", self.paint(GUTTER, "..."));
+            }
+
+            let line_range = index.line_range(*line).unwrap();
+            let text = source[line_range.clone()].trim_end_matches(['\n', '\r']);
+
+            _ = writeln!(
+                output,
+                "{}{}",
+                self.paint(GUTTER, format!("{:>width$} |", line + 1)),
+                if text.is_empty() {
+                    String::new()
+                } else {
+                    format!(" {}", text)
+                }
+            );
+
+            let line_chars = text.chars().count();
+
+            for (primary, range, message) in &labels {
+                let start = index.position(range.start, ColumnUnit::Char).unwrap();
+                let end = index.position(range.end, ColumnUnit::Char).unwrap();
+
+                if *line < start.line || *line > end.line {
+                    continue;
+                }
+
+                let from = if *line == start.line { start.column } else { 0 };
+                let to = if *line == end.line {
+                    end.column
+                } else {
+                    line_chars
+                };
+
+                // an empty region still gets one marker.
+                let len = to.saturating_sub(from).max(1);
+                let (marker, marker_color) = if *primary {
+                    ("^", color)
+                } else {
+                    ("-", GUTTER)
+                };
+
+                let mut underline = format!("{}{}", " ".repeat(from), marker.repeat(len));
+
+                // the message goes after the last line of the region.
+                if *line == end.line && !message.is_empty() {
+                    underline = format!("{} {}", underline, message);
+                }
+
+                _ = writeln!(output, "{} {}", gutter, self.paint(marker_color, underline));
+            }
+        }
+
+        if !diagnostic.notes.is_empty() {
+            if !lines.is_empty() {
+                _ = writeln!(output, "{}", gutter);
+            }
+
+            for note in &diagnostic.notes {
+                _ = writeln!(
+                    output,
+                    "{} {} {}",
+                    " ".repeat(width),
+                    self.paint(GUTTER, "="),
+                    format_args!("{}: {}", self.paint("\x1b[1m", "note"), note)
+                );
+            }
+        }
+
+        output
+    }
+}
+
+/// Convert a `span` of global offsets into a range of `source`, returns `None` for `Span::None`.
+fn local_range(span: &Span, base: usize, len: usize) -> Option<std::ops::Range<usize>> {
+    let (start, end) = match span {
+        Span::None => return None,
+        Span::Range(range) => (range.start, range.end),
+        Span::RangeFrom(range) => (range.start, base + len),
+        Span::RangeTo(range) => (base, range.end),
+        Span::RangeFull => (base, base + len),
+    };
+
+    let start = start.checked_sub(base)?.min(len);
+    let end = end.checked_sub(base)?.clamp(start, len);
+
+    Some(start..end)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ControlFlow, Kind, ParseError, SourceMap, Span};
+
+    use super::{Diagnostic, Renderer, Severity};
+
+    #[test]
+    fn test_render() {
+        let source = "fn main() {\n    let x = (1;\n}\n";
+
+        let diagnostic = Diagnostic::error("expected `)`, found `;`", Span::Range(26..27))
+            .with_message("expected `)`")
+            .with_label(Span::Range(24..25), "unclosed delimiter")
+            .with_note("in let statement");
+
+        assert_eq!(
+            Renderer::plain().render(&diagnostic, "main.x", source),
+            "\
+error: expected `)`, found `;`
+ --> main.x:2:15
+  |
+2 |     let x = (1;
+  |               ^ expected `)`
+  |             - unclosed delimiter
+  |
+  = note: in let statement
+"
+        );
+    }
+
+    #[test]
+    fn test_render_multi_line() {
+        let source = "a\nbc {\n  d\n}\n\n\nlast";
+
+        let diagnostic = Diagnostic::new(Severity::Warning, "unused block", Span::Range(5..12))
+            .with_message("this block")
+            .with_label(Span::Range(15..19), "");
+
+        assert_eq!(
+            Renderer::plain().render(&diagnostic, "a.x", source),
+            "\
+warning: unused block
+ --> a.x:2:4
+  |
+2 | bc {
+  |    ^
+3 |   d
+  | ^^^
+4 | }
+  | ^ this block
+...
+7 | last
+  | ----
+"
+        );
+    }
+
+    #[test]
+    fn test_render_error() {
+        let mut map = SourceMap::new();
+        map.add("empty.x", "");
+        let file = map.add("main.x", "let");
+
+        let err = Kind::Keyword(ControlFlow::Fatal, Span::RangeFrom(4..)).context("statement");
+        let diagnostic = Diagnostic::from_error(&err);
+
+        assert_eq!(
+            Renderer::plain().render_file(&diagnostic, map.get(file).unwrap()),
+            "\
+error: Error from `keyword` combinator
+ --> main.x:1:4
+  |
+1 | let
+  |    ^
+  |
+  = note: in statement
+"
+        );
+
+        let colored = Renderer::colored().render_file(&diagnostic, map.get(file).unwrap());
+        assert!(colored.starts_with("\x1b[1;31merror\x1b[0m"));
+    }
+}
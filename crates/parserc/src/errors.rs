@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 use crate::Span;
 
 /// A variant type to control error handle.
@@ -9,6 +11,9 @@ pub enum ControlFlow {
     /// A recovable error generally lead to a retrospective parsing process.
     Recovable,
     /// This error means that the parsing process failed because it reached the end of the input stream.
+    ///
+    /// On a [`partial`](crate::Input::is_partial) input the error span is the missing region,
+    /// see [`ParseError::needed`].
     Incomplete,
 }
 
@@ -37,6 +42,40 @@ pub enum Kind {
     TakeWhileRange(ControlFlow, Span),
     #[error("Error from `take_while_from`")]
     TakeWhileFrom(ControlFlow, Span),
+    #[error("Error from `verify` combinator")]
+    Verify(ControlFlow, Span),
+    #[error("Error from `alt` combinator, all branches failed")]
+    Alt(ControlFlow, Span),
+    #[error("Error from `repeat` combinator, repetitions out of range")]
+    Repeat(ControlFlow, Span),
+    #[error("Error from `separated_list` combinator, repetitions out of range")]
+    SeparatedList(ControlFlow, Span),
+    #[error("Error from repetition, an iteration consumed no input")]
+    NoProgress(ControlFlow, Span),
+    #[error("in {0} → {1}")]
+    Context(&'static str, Box<Kind>),
+    #[error("Error from `take_while`")]
+    TakeWhile(ControlFlow, Span),
+    #[error("Error from `take`")]
+    Take(ControlFlow, Span),
+    #[error("Error from `leb128`, the value overflows")]
+    Leb128(ControlFlow, Span),
+    #[error("Error from `length_prefixed`, the length is out of range")]
+    LengthPrefixed(ControlFlow, Span),
+    #[error("Error from `magic`")]
+    Magic(ControlFlow, Span),
+    #[error("Error from aligned bit readers, the position is not on a byte boundary")]
+    Unaligned(ControlFlow, Span),
+}
+
+impl Kind {
+    /// Returns the innermost error without context frames.
+    pub fn root(&self) -> &Kind {
+        match self {
+            Kind::Context(_, inner) => inner.root(),
+            kind => kind,
+        }
+    }
 }
 
 /// A error type returns by parser combinators.
@@ -48,6 +87,40 @@ pub trait ParseError: From<Kind> {
 
     /// Ensure this error is an fatal error.
     fn into_fatal(self) -> Self;
+
+    /// Push a context frame `label` onto this error, the default implementation discards it.
+    #[inline]
+    fn context(self, _label: &'static str) -> Self {
+        self
+    }
+
+    /// Returns context labels from the outermost frame to the innermost one.
+    #[inline]
+    fn contexts(&self) -> Vec<&'static str> {
+        vec![]
+    }
+
+    /// Returns the message of the innermost error, without the frames returned by [`contexts`](ParseError::contexts).
+    #[inline]
+    fn root_message(&self) -> String
+    where
+        Self: Display,
+    {
+        self.to_string()
+    }
+
+    /// Returns how many more bytes an [`Incomplete`](ControlFlow::Incomplete) error needs.
+    ///
+    /// The span of an incomplete error is the missing region, returns `None` if its length is unknown
+    /// or this error is not incomplete.
+    #[inline]
+    fn needed(&self) -> Option<usize> {
+        if self.control_flow() != ControlFlow::Incomplete {
+            return None;
+        }
+
+        self.to_span().len().filter(|len| *len > 0)
+    }
 }
 
 impl ParseError for Kind {
@@ -64,6 +137,18 @@ impl ParseError for Kind {
             Kind::Token(_, control_flow, _) => *control_flow,
             Kind::TakeWhileRange(control_flow, _) => *control_flow,
             Kind::TakeWhileFrom(control_flow, _) => *control_flow,
+            Kind::Verify(control_flow, _) => *control_flow,
+            Kind::Alt(control_flow, _) => *control_flow,
+            Kind::Repeat(control_flow, _) => *control_flow,
+            Kind::SeparatedList(control_flow, _) => *control_flow,
+            Kind::NoProgress(control_flow, _) => *control_flow,
+            Kind::TakeWhile(control_flow, _) => *control_flow,
+            Kind::Take(control_flow, _) => *control_flow,
+            Kind::Leb128(control_flow, _) => *control_flow,
+            Kind::LengthPrefixed(control_flow, _) => *control_flow,
+            Kind::Magic(control_flow, _) => *control_flow,
+            Kind::Unaligned(control_flow, _) => *control_flow,
+            Kind::Context(_, inner) => inner.control_flow(),
         }
     }
 
@@ -80,6 +165,18 @@ impl ParseError for Kind {
             Kind::LimitsTo(_, span) => Kind::LimitsTo(ControlFlow::Fatal, span),
             Kind::Limits(_, span) => Kind::Limits(ControlFlow::Fatal, span),
             Kind::LimitsFrom(_, span) => Kind::LimitsFrom(ControlFlow::Fatal, span),
+            Kind::Verify(_, span) => Kind::Verify(ControlFlow::Fatal, span),
+            Kind::Alt(_, span) => Kind::Alt(ControlFlow::Fatal, span),
+            Kind::Repeat(_, span) => Kind::Repeat(ControlFlow::Fatal, span),
+            Kind::SeparatedList(_, span) => Kind::SeparatedList(ControlFlow::Fatal, span),
+            Kind::NoProgress(_, span) => Kind::NoProgress(ControlFlow::Fatal, span),
+            Kind::TakeWhile(_, span) => Kind::TakeWhile(ControlFlow::Fatal, span),
+            Kind::Take(_, span) => Kind::Take(ControlFlow::Fatal, span),
+            Kind::Leb128(_, span) => Kind::Leb128(ControlFlow::Fatal, span),
+            Kind::LengthPrefixed(_, span) => Kind::LengthPrefixed(ControlFlow::Fatal, span),
+            Kind::Magic(_, span) => Kind::Magic(ControlFlow::Fatal, span),
+            Kind::Unaligned(_, span) => Kind::Unaligned(ControlFlow::Fatal, span),
+            Kind::Context(label, inner) => Kind::Context(label, Box::new(inner.into_fatal())),
         }
     }
 
@@ -96,6 +193,38 @@ impl ParseError for Kind {
             Kind::TakeWhileRange(_, span) => span.clone(),
             Kind::TakeWhileFrom(_, span) => span.clone(),
             Kind::LimitsFrom(_, span) => span.clone(),
+            Kind::Verify(_, span) => span.clone(),
+            Kind::Alt(_, span) => span.clone(),
+            Kind::Repeat(_, span) => span.clone(),
+            Kind::SeparatedList(_, span) => span.clone(),
+            Kind::NoProgress(_, span) => span.clone(),
+            Kind::TakeWhile(_, span) => span.clone(),
+            Kind::Take(_, span) => span.clone(),
+            Kind::Leb128(_, span) => span.clone(),
+            Kind::LengthPrefixed(_, span) => span.clone(),
+            Kind::Magic(_, span) => span.clone(),
+            Kind::Unaligned(_, span) => span.clone(),
+            Kind::Context(_, inner) => inner.to_span(),
+        }
+    }
+
+    fn context(self, label: &'static str) -> Self {
+        Kind::Context(label, Box::new(self))
+    }
+
+    fn contexts(&self) -> Vec<&'static str> {
+        let mut labels = vec![];
+        let mut kind = self;
+
+        while let Kind::Context(label, inner) = kind {
+            labels.push(*label);
+            kind = inner;
         }
+
+        labels
+    }
+
+    fn root_message(&self) -> String {
+        self.root().to_string()
     }
 }
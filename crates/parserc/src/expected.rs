@@ -0,0 +1,364 @@
+//! Furthest failure tracking for "expected one of ..." errors.
+
+use std::{
+    cell::RefCell,
+    fmt::{Debug, Display},
+    rc::Rc,
+};
+
+use crate::{
+    Span,
+    input::{AsBytes, AsStr, Find, Input, Item, StartWith},
+    memo::MemoTable,
+    trivia::SkipTrivia,
+};
+
+/// Names of the syntaxes tried at the furthest failure position.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Expected {
+    /// Position of the furthest failure in the whole source code.
+    pub offset: usize,
+    /// Region of the item found at `offset`, `None` at the end of input.
+    pub found: Option<Span>,
+    /// Expected names in the order they were tried, without duplicates.
+    pub names: Vec<String>,
+}
+
+impl Expected {
+    /// Returns true if no failure is recorded.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    /// Record `name` as expected at `offset`.
+    ///
+    /// A further `offset` replaces all recorded names, a closer one is ignored.
+    pub fn insert(&mut self, offset: usize, found: Option<Span>, name: impl Display) {
+        if self.is_empty() || offset > self.offset {
+            self.offset = offset;
+            self.found = found;
+            self.names.clear();
+        } else if offset < self.offset {
+            return;
+        }
+
+        let name = name.to_string();
+
+        if !self.names.contains(&name) {
+            self.names.push(name);
+        }
+    }
+
+    /// Format as `expected a, b or c, found x`, `source` is the whole source code.
+    ///
+    /// `found` is widened to the next char boundary, so a byte level failure shows the whole char.
+    pub fn message(&self, source: &str) -> String {
+        let expected = match self.names.as_slice() {
+            [] => "nothing".to_string(),
+            [name] => name.clone(),
+            [names @ .., last] => format!("{} or {}", names.join(", "), last),
+        };
+
+        let found = if self.offset < source.len() {
+            let range = match &self.found {
+                Some(Span::Range(range)) => range.clone(),
+                _ => self.offset..self.offset + 1,
+            };
+
+            let end = (range.end..=source.len())
+                .find(|end| source.is_char_boundary(*end))
+                .unwrap_or(source.len());
+
+            source.get(range.start..end)
+        } else {
+            None
+        };
+
+        let found = found.map_or("end of input".to_string(), |found| format!("`{}`", found));
+
+        format!("expected {}, found {}", expected, found)
+    }
+}
+
+/// An `Input` wrapper that tracks the furthest failure position and the [`Expected`] names there.
+///
+/// Derived tokens, [`Byte`](crate::syntax::Byte), [`Char`](crate::syntax::Char) and
+/// [`label`](crate::Parser::label)ed parsers report their names on failure.
+pub struct Furthest<I> {
+    input: I,
+    expected: Rc<RefCell<Expected>>,
+}
+
+impl<I> Furthest<I> {
+    /// Wrap `input` with an empty [`Expected`] set.
+    #[inline]
+    pub fn new(input: I) -> Self {
+        Self {
+            input,
+            expected: Default::default(),
+        }
+    }
+
+    /// Returns the wrapped input.
+    #[inline]
+    pub fn as_inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Consumes this wrapper and returns the wrapped input.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    /// Returns a copy of the furthest failure recorded so far.
+    #[inline]
+    pub fn expected(&self) -> Expected {
+        self.expected.borrow().clone()
+    }
+}
+
+impl<I> From<I> for Furthest<I> {
+    fn from(value: I) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<I> Clone for Furthest<I>
+where
+    I: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            input: self.input.clone(),
+            expected: self.expected.clone(),
+        }
+    }
+}
+
+impl<I> Debug for Furthest<I>
+where
+    I: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Furthest").field(&self.input).finish()
+    }
+}
+
+impl<I> PartialEq for Furthest<I>
+where
+    I: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.input == other.input
+    }
+}
+
+impl<I> Input for Furthest<I>
+where
+    I: Input,
+{
+    type Item = I::Item;
+
+    type Error = I::Error;
+
+    type Iter = I::Iter;
+
+    type IterIndices = I::IterIndices;
+
+    const HAS_TRIVIA: bool = I::HAS_TRIVIA;
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.input.len()
+    }
+
+    #[inline]
+    fn split_to(&mut self, at: usize) -> Self {
+        Self {
+            input: self.input.split_to(at),
+            expected: self.expected.clone(),
+        }
+    }
+
+    #[inline]
+    fn split_off(&mut self, at: usize) -> Self {
+        Self {
+            input: self.input.split_off(at),
+            expected: self.expected.clone(),
+        }
+    }
+
+    #[inline]
+    fn iter(&self) -> Self::Iter {
+        self.input.iter()
+    }
+
+    #[inline]
+    fn iter_indices(&self) -> Self::IterIndices {
+        self.input.iter_indices()
+    }
+
+    #[inline]
+    fn start(&self) -> usize {
+        self.input.start()
+    }
+
+    #[inline]
+    fn end(&self) -> usize {
+        self.input.end()
+    }
+
+    #[inline]
+    fn memo_table(&self) -> Option<&MemoTable> {
+        self.input.memo_table()
+    }
+
+    #[inline]
+    fn is_partial(&self) -> bool {
+        self.input.is_partial()
+    }
+
+    #[inline]
+    fn skip_trivia(&mut self) -> Result<(), Self::Error> {
+        self.input.skip_trivia()
+    }
+
+    #[inline]
+    fn replace_trivia(&mut self, _skip: Option<SkipTrivia<Self>>) -> Option<SkipTrivia<Self>> {
+        crate::trivia::assert_outermost::<I>();
+        None
+    }
+
+    #[inline]
+    fn recover(&self, error: Self::Error) -> Result<(), Self::Error> {
+        self.input.recover(error)
+    }
+
+    fn report_expected(&self, name: impl Display) {
+        let found = self.iter().next().map(|item| self.to_span_at(item.len()));
+
+        self.expected.borrow_mut().insert(self.start(), found, name);
+    }
+}
+
+impl<I> AsBytes for Furthest<I>
+where
+    I: AsBytes,
+{
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        self.input.as_bytes()
+    }
+}
+
+impl<I> AsStr for Furthest<I>
+where
+    I: AsStr,
+{
+    #[inline]
+    fn as_str(&self) -> &str {
+        self.input.as_str()
+    }
+}
+
+impl<I, N> StartWith<N> for Furthest<I>
+where
+    I: StartWith<N>,
+{
+    #[inline]
+    fn starts_with(&self, needle: N) -> Option<usize> {
+        self.input.starts_with(needle)
+    }
+
+    #[inline]
+    fn needed(&self, needle: N) -> Option<usize> {
+        self.input.needed(needle)
+    }
+}
+
+impl<I, N> Find<N> for Furthest<I>
+where
+    I: Find<N>,
+{
+    #[inline]
+    fn find(&self, needle: N) -> Option<usize> {
+        self.input.find(needle)
+    }
+}
+
+#[cfg(feature = "lang")]
+impl<I> crate::lang::LangInput for Furthest<I> where I: crate::lang::LangInput {}
+
+#[cfg(all(test, feature = "lang"))]
+mod tests {
+    use crate::{Kind, Parser, Span, keyword, lang::TokenStream};
+
+    use super::{Expected, Furthest};
+
+    type TS<'a> = TokenStream<'a, Kind>;
+
+    #[test]
+    fn test_insert() {
+        let mut expected = Expected::default();
+
+        expected.insert(2, None, "a");
+        expected.insert(1, None, "b");
+        expected.insert(2, None, "c");
+        expected.insert(2, None, "a");
+
+        assert_eq!(expected.offset, 2);
+        assert_eq!(expected.names, vec!["a", "c"]);
+
+        expected.insert(3, Some(Span::Range(3..4)), "d");
+        assert_eq!(expected.names, vec!["d"]);
+        assert_eq!(expected.message("abc;"), "expected d, found `;`");
+
+        expected.insert(4, None, "e");
+        assert_eq!(expected.message("abc;"), "expected e, found end of input");
+    }
+
+    #[test]
+    fn test_label() {
+        let mut input = Furthest::new(TS::from("let x;"));
+
+        let parser = keyword("fn")
+            .label("`fn`")
+            .or(keyword("let")
+                .label("`let`")
+                .then(keyword(" y").label("`y`"))
+                .map(|(_, y)| y))
+            .or(keyword("struct").label("`struct`"));
+
+        assert!(parser.ok().parse(&mut input.clone()).unwrap().is_none());
+        assert_eq!(
+            input.expected().message("let x;"),
+            "expected `y`, found ` `"
+        );
+
+        input = Furthest::new(TS::from("x"));
+        assert!(
+            keyword("fn")
+                .label("`fn`")
+                .or(keyword("let").label("`let`"))
+                .parse(&mut input)
+                .is_err()
+        );
+        assert_eq!(
+            input.expected().message("x"),
+            "expected `fn` or `let`, found `x`"
+        );
+
+        // the found item is a whole char, not its first byte.
+        input = Furthest::new(TS::from("let é"));
+        assert!(
+            keyword("let ")
+                .then(keyword("x").label("`x`"))
+                .parse(&mut input)
+                .is_err()
+        );
+        assert_eq!(input.expected().message("let é"), "expected `x`, found `é`");
+    }
+}
@@ -1,11 +1,22 @@
-use std::{cmp, fmt::Debug};
+use std::{
+    cmp,
+    fmt::{Debug, Display},
+};
 
-use crate::{ParseError, Span};
+use crate::{ControlFlow, Kind, MemoTable, ParseError, SkipTrivia, Span};
 
 /// An extension trait provides extra `starts_with` func to `Input`.
 pub trait StartWith<Needle> {
     /// Convert the input type to a byte slice
     fn starts_with(&self, needle: Needle) -> Option<usize>;
+
+    /// Returns how many items of `needle` are past the end, if this input is a proper prefix of `needle`.
+    ///
+    /// Streaming parsers use it to tell a truncated match from a mismatch, the default returns `None`.
+    #[inline]
+    fn needed(&self, _needle: Needle) -> Option<usize> {
+        None
+    }
 }
 
 /// An extension trait providers extra `find` func to `Input`.
@@ -21,6 +32,7 @@ pub trait AsBytes {
 }
 
 /// A trait to fetch item length.
+#[allow(clippy::len_without_is_empty)]
 pub trait Length {
     /// Returns item length.
     fn len(&self) -> usize;
@@ -38,6 +50,12 @@ impl Length for &[u8] {
     }
 }
 
+impl<const N: usize> Length for &[u8; N] {
+    fn len(&self) -> usize {
+        N
+    }
+}
+
 /// Convert `Input` as `&str`
 pub trait AsStr {
     /// Convert the input type to a str slice
@@ -45,6 +63,7 @@ pub trait AsStr {
 }
 
 /// The item type of the input sequence.
+#[allow(clippy::len_without_is_empty)]
 pub trait Item: PartialEq + Clone + Copy + Debug {
     fn len(&self) -> usize;
 }
@@ -63,6 +82,13 @@ impl Item for char {
     }
 }
 
+impl Item for bool {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        1
+    }
+}
+
 /// Input sequence for source code.
 pub trait Input: PartialEq + Debug {
     /// Sequeue item.
@@ -74,6 +100,9 @@ pub trait Input: PartialEq + Debug {
     /// Iterator type returns by [`iter_indices`](Input::iter_indices).
     type IterIndices: Iterator<Item = (usize, Self::Item)>;
 
+    /// True if a [`Trivia`](crate::Trivia) wrapper is inside this input, wrappers forward it.
+    const HAS_TRIVIA: bool = false;
+
     // Returns current input sequence length.
     fn len(&self) -> usize;
 
@@ -111,9 +140,89 @@ pub trait Input: PartialEq + Debug {
         Span::Range(self.start()..self.end())
     }
 
+    /// Returns the packrat table shared by this input, only [`Memo`](crate::Memo) inputs have one.
+    #[inline]
+    fn memo_table(&self) -> Option<&MemoTable> {
+        None
+    }
+
+    /// Collect a `fatal` error and continue parsing, only [`Recovery`](crate::Recovery) inputs support it.
+    ///
+    /// Returns the `error` back if this input can't recover.
+    #[inline]
+    fn recover(&self, error: Self::Error) -> Result<(), Self::Error> {
+        Err(error)
+    }
+
+    /// Report that the syntax `name` was expected at the start of this input.
+    ///
+    /// Only [`Furthest`](crate::Furthest) inputs record it, the default does nothing.
+    #[inline]
+    fn report_expected(&self, _name: impl Display) {}
+
+    /// Returns true if more data may follow the end of this input, only [`Partial`](crate::Partial) inputs are.
+    ///
+    /// Combinators reaching the end of a partial input return [`Incomplete`](ControlFlow::Incomplete) errors
+    /// instead of succeeding or failing early.
+    #[inline]
+    fn is_partial(&self) -> bool {
+        false
+    }
+
+    /// Skip the trivia (whitespace, comments ...) at the start of this input,
+    /// only [`Trivia`](crate::Trivia) inputs with an installed skipper do it.
+    ///
+    /// Derived syntaxes call it before every field and around tokens.
+    #[inline]
+    fn skip_trivia(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// Install the trivia skipper for the syntaxes parsed next, returns the previous one.
+    ///
+    /// Only [`Trivia`](crate::Trivia) inputs hold a skipper, the default ignores it and returns `None`.
+    /// The skipper is typed on this input, so other wrappers can't forward it to an inner `Trivia`
+    /// and fail to compile instead.
+    #[inline]
+    fn replace_trivia(&mut self, _skip: Option<SkipTrivia<Self>>) -> Option<SkipTrivia<Self>> {
+        None
+    }
+
+    /// Returns the installed trivia skipper, only [`Trivia`](crate::Trivia) inputs hold one.
+    #[inline]
+    fn trivia(&self) -> Option<SkipTrivia<Self>> {
+        None
+    }
+
+    /// Ensure this input has advanced past the `start` position of the previous iteration.
+    ///
+    /// A repetition calls this after every iteration, if nothing is consumed
+    /// returns a [`fatal`](ControlFlow::Fatal) [`Kind::NoProgress`] error instead of looping forever.
+    #[inline]
+    fn ensure_progress(&self, start: usize) -> Result<(), Self::Error> {
+        if self.start() == start {
+            return Err(Kind::NoProgress(ControlFlow::Fatal, self.to_span_at(0)).into());
+        }
+
+        Ok(())
+    }
+
     /// Returns the region from `start` of this input to `at` position.
     #[inline]
     fn to_span_at(&self, at: usize) -> Span {
         Span::Range(self.start()..cmp::min(self.start() + at, self.end()))
     }
+
+    /// Returns the missing region of `len` items past the end of this input, used by
+    /// [`Incomplete`](ControlFlow::Incomplete) errors.
+    ///
+    /// A zero `len` means the length is unknown, returns `end..`.
+    #[inline]
+    fn needed_at(&self, len: usize) -> Span {
+        if len == 0 {
+            Span::RangeFrom(self.end()..)
+        } else {
+            Span::Range(self.end()..self.end() + len)
+        }
+    }
 }
@@ -22,6 +22,7 @@ pub trait LangInput:
 }
 
 /// `Input` for compute language parsing.
+#[allow(clippy::derived_hash_with_manual_eq)]
 #[derive(Eq, PartialOrd, Ord, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TokenStream<'a, E> {
@@ -167,6 +168,15 @@ impl<'a, E> StartWith<&str> for TokenStream<'a, E> {
             None
         }
     }
+
+    #[inline]
+    fn needed(&self, needle: &str) -> Option<usize> {
+        if needle.len() > self.value.len() && needle.as_bytes().starts_with(self.as_bytes()) {
+            Some(needle.len() - self.value.len())
+        } else {
+            None
+        }
+    }
 }
 
 impl<'a, E> StartWith<&[u8]> for TokenStream<'a, E> {
@@ -178,6 +188,15 @@ impl<'a, E> StartWith<&[u8]> for TokenStream<'a, E> {
             None
         }
     }
+
+    #[inline]
+    fn needed(&self, needle: &[u8]) -> Option<usize> {
+        if needle.len() > self.value.len() && needle.starts_with(self.as_bytes()) {
+            Some(needle.len() - self.value.len())
+        } else {
+            None
+        }
+    }
 }
 
 impl<'a, const N: usize, E> StartWith<&[u8; N]> for TokenStream<'a, E> {
@@ -189,6 +208,15 @@ impl<'a, const N: usize, E> StartWith<&[u8; N]> for TokenStream<'a, E> {
             None
         }
     }
+
+    #[inline]
+    fn needed(&self, needle: &[u8; N]) -> Option<usize> {
+        if needle.len() > self.value.len() && needle.starts_with(self.as_bytes()) {
+            Some(needle.len() - self.value.len())
+        } else {
+            None
+        }
+    }
 }
 
 impl<'a, E> Find<&str> for TokenStream<'a, E> {
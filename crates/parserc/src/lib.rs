@@ -13,13 +13,50 @@ pub use span::*;
 mod parser;
 pub use parser::*;
 
+mod repeat;
+
+mod boxed;
+pub use boxed::*;
+
+mod memo;
+pub use memo::*;
+
+mod recover;
+pub use recover::*;
+
+mod expected;
+pub use expected::*;
+
+mod stream;
+pub use stream::*;
+
+mod trivia;
+pub use trivia::*;
+
 mod c;
 pub use c::*;
 
+pub mod diagnostic;
+
+mod alt;
+pub use alt::*;
+
 #[cfg(feature = "lang")]
 #[cfg_attr(docsrs, doc(cfg(feature = "lang")))]
 pub mod lang;
 
+#[cfg(feature = "binary")]
+#[cfg_attr(docsrs, doc(cfg(feature = "binary")))]
+pub mod binary;
+
+#[cfg(feature = "binary")]
+#[cfg_attr(docsrs, doc(cfg(feature = "binary")))]
+pub mod bits;
+
 #[cfg(feature = "syntax")]
 #[cfg_attr(docsrs, doc(cfg(feature = "syntax")))]
 pub mod syntax;
+
+#[cfg(feature = "syntax")]
+#[cfg_attr(docsrs, doc(cfg(feature = "syntax")))]
+pub mod pratt;
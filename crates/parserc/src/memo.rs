@@ -0,0 +1,288 @@
+//! Memoization of rule results for derived grammars.
+
+use std::{
+    any::TypeId,
+    cell::RefCell,
+    collections::HashMap,
+    fmt::{Debug, Display},
+    rc::Rc,
+};
+
+use crate::{
+    input::{AsBytes, AsStr, Find, Input, StartWith},
+    trivia::SkipTrivia,
+};
+
+/// A successful parsing recorded by [`MemoTable`].
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub struct MemoEntry {
+    /// Index of the enum variant that matched.
+    pub variant: usize,
+    /// End position of the matched syntax.
+    pub end: usize,
+}
+
+/// Identifies a result recorded by [`MemoTable`]: the rule type, its start position
+/// and the trivia skipper installed there, which changes what the rule consumes.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct MemoKey {
+    rule: TypeId,
+    start: usize,
+    trivia: Option<TypeId>,
+}
+
+impl MemoKey {
+    /// Returns the key of rule `T` at the start of `input`.
+    #[inline]
+    pub fn new<T, I>(input: &I) -> Self
+    where
+        T: ?Sized,
+        I: Input,
+    {
+        Self {
+            // syntaxes borrow the input, `typeid` ignores their lifetimes.
+            rule: typeid::of::<T>(),
+            start: input.start(),
+            trivia: input.trivia().map(|skip| skip.tag()),
+        }
+    }
+
+    /// Returns the start position of the rule.
+    #[inline]
+    pub fn start(&self) -> usize {
+        self.start
+    }
+}
+
+/// A table maps [`MemoKey`]s to the parsing results.
+///
+/// A `None` result means the rule failed with a `non-fatal` error at that position.
+/// Parsed syntaxes borrow the input, so a success records the matching variant and its end
+/// instead of the syntax itself. Unlike a packrat parser, a success is parsed again
+/// (only its matching variant), so parsing is not guaranteed to take linear time.
+#[derive(Debug, Default)]
+pub struct MemoTable(RefCell<HashMap<MemoKey, Option<MemoEntry>>>);
+
+impl MemoTable {
+    /// Returns the recorded result of `key`, if any.
+    #[inline]
+    pub fn get(&self, key: &MemoKey) -> Option<Option<MemoEntry>> {
+        self.0.borrow().get(key).copied()
+    }
+
+    /// Record the result of `key`.
+    #[inline]
+    pub fn insert(&self, key: MemoKey, entry: Option<MemoEntry>) {
+        self.0.borrow_mut().insert(key, entry);
+    }
+
+    /// Returns the number of recorded results.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    /// Returns true if no result is recorded.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.borrow().is_empty()
+    }
+}
+
+/// An `Input` wrapper that shares a [`MemoTable`] between all its slices.
+///
+/// Enums derived with `#[syntax(memo)]` record which variant matched at each position,
+/// so a failed rule is never retried and a successful one replays only the matching variant.
+#[derive(Clone)]
+pub struct Memo<I> {
+    input: I,
+    table: Rc<MemoTable>,
+}
+
+impl<I> Memo<I> {
+    /// Wrap `input` with a new empty [`MemoTable`].
+    #[inline]
+    pub fn new(input: I) -> Self {
+        Self {
+            input,
+            table: Default::default(),
+        }
+    }
+
+    /// Returns the wrapped input.
+    #[inline]
+    pub fn as_inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Consumes this wrapper and returns the wrapped input.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    /// Returns the shared [`MemoTable`].
+    #[inline]
+    pub fn table(&self) -> &MemoTable {
+        &self.table
+    }
+}
+
+impl<I> From<I> for Memo<I> {
+    fn from(value: I) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<I> Debug for Memo<I>
+where
+    I: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Memo").field(&self.input).finish()
+    }
+}
+
+impl<I> PartialEq for Memo<I>
+where
+    I: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.input == other.input
+    }
+}
+
+impl<I> Input for Memo<I>
+where
+    I: Input,
+{
+    type Item = I::Item;
+
+    type Error = I::Error;
+
+    type Iter = I::Iter;
+
+    type IterIndices = I::IterIndices;
+
+    const HAS_TRIVIA: bool = I::HAS_TRIVIA;
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.input.len()
+    }
+
+    #[inline]
+    fn split_to(&mut self, at: usize) -> Self {
+        Self {
+            input: self.input.split_to(at),
+            table: self.table.clone(),
+        }
+    }
+
+    #[inline]
+    fn split_off(&mut self, at: usize) -> Self {
+        Self {
+            input: self.input.split_off(at),
+            table: self.table.clone(),
+        }
+    }
+
+    #[inline]
+    fn iter(&self) -> Self::Iter {
+        self.input.iter()
+    }
+
+    #[inline]
+    fn iter_indices(&self) -> Self::IterIndices {
+        self.input.iter_indices()
+    }
+
+    #[inline]
+    fn start(&self) -> usize {
+        self.input.start()
+    }
+
+    #[inline]
+    fn end(&self) -> usize {
+        self.input.end()
+    }
+
+    #[inline]
+    fn memo_table(&self) -> Option<&MemoTable> {
+        Some(&self.table)
+    }
+
+    #[inline]
+    fn recover(&self, error: Self::Error) -> Result<(), Self::Error> {
+        self.input.recover(error)
+    }
+
+    #[inline]
+    fn report_expected(&self, name: impl Display) {
+        self.input.report_expected(name);
+    }
+
+    #[inline]
+    fn is_partial(&self) -> bool {
+        self.input.is_partial()
+    }
+
+    #[inline]
+    fn skip_trivia(&mut self) -> Result<(), Self::Error> {
+        self.input.skip_trivia()
+    }
+
+    #[inline]
+    fn replace_trivia(&mut self, _skip: Option<SkipTrivia<Self>>) -> Option<SkipTrivia<Self>> {
+        crate::trivia::assert_outermost::<I>();
+        None
+    }
+}
+
+impl<I> AsBytes for Memo<I>
+where
+    I: AsBytes,
+{
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        self.input.as_bytes()
+    }
+}
+
+impl<I> AsStr for Memo<I>
+where
+    I: AsStr,
+{
+    #[inline]
+    fn as_str(&self) -> &str {
+        self.input.as_str()
+    }
+}
+
+impl<I, N> StartWith<N> for Memo<I>
+where
+    I: StartWith<N>,
+{
+    #[inline]
+    fn starts_with(&self, needle: N) -> Option<usize> {
+        self.input.starts_with(needle)
+    }
+
+    #[inline]
+    fn needed(&self, needle: N) -> Option<usize> {
+        self.input.needed(needle)
+    }
+}
+
+impl<I, N> Find<N> for Memo<I>
+where
+    I: Find<N>,
+{
+    #[inline]
+    fn find(&self, needle: N) -> Option<usize> {
+        self.input.find(needle)
+    }
+}
+
+#[cfg(feature = "lang")]
+impl<I> crate::lang::LangInput for Memo<I> where I: crate::lang::LangInput {}
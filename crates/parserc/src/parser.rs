@@ -1,8 +1,14 @@
 //! Traits for parser combinators.
 
+use std::ops::RangeBounds;
+
 use crate::{
-    errors::{ControlFlow, ParseError},
+    Span, Spanned,
+    boxed::BoxedParser,
+    errors::{ControlFlow, Kind, ParseError},
     input::Input,
+    recover::{Recover, Recovered},
+    repeat::{FoldMany, SeparatedList},
 };
 
 /// A parsing combinator should implement this trait.
@@ -12,10 +18,25 @@ where
 {
     type Output;
 
-    /// Consumes itself and parses the input stream to generate the `output` product.
-    fn parse(self, input: &mut I) -> Result<Self::Output, I::Error>;
+    /// Parses the input stream to generate the `output` product.
+    ///
+    /// A parser does not consume itself, so it can be executed many times.
+    fn parse(&mut self, input: &mut I) -> Result<Self::Output, I::Error>;
+
+    /// Borrows a parser, rather than consuming it.
+    ///
+    /// This is useful to apply combinators to a parser and still keep ownership of it.
+    #[inline]
+    fn by_ref(&mut self) -> impl Parser<I, Output = Self::Output>
+    where
+        Self: Sized,
+    {
+        ByRef(self)
+    }
 
     /// Creates a new parser that converts `non-fatal` error into `None` value.
+    ///
+    /// An [`Incomplete`](ControlFlow::Incomplete) error of a [`partial`](Input::is_partial) input is returned as is.
     #[inline]
     fn ok(self) -> impl Parser<I, Output = Option<Self::Output>>
     where
@@ -29,13 +50,15 @@ where
     #[inline]
     fn map<F, O>(self, f: F) -> impl Parser<I, Output = O>
     where
-        F: FnOnce(Self::Output) -> O,
+        F: FnMut(Self::Output) -> O,
         Self: Sized,
     {
         Map(self, f)
     }
 
     /// Creates a parser that convert all `non-fatal` error into [`fatal`](ControlFlow::Fatal) error.
+    ///
+    /// An [`Incomplete`](ControlFlow::Incomplete) error of a [`partial`](Input::is_partial) input is kept.
     #[inline]
     fn fatal(self) -> impl Parser<I, Output = Self::Output>
     where
@@ -50,7 +73,7 @@ where
     where
         Self: Sized,
     {
-        self.map(|v| Box::new(v))
+        self.map(Box::new)
     }
 
     /// Executre another `Parser` if this one returns a `non-fatal` error.
@@ -63,22 +86,299 @@ where
     {
         Or(self, parser)
     }
+
+    /// Erase the type of this parser, see [`BoxedParser`].
+    #[inline]
+    fn into_boxed<'a>(self) -> BoxedParser<'a, I, Self::Output>
+    where
+        Self: Sized + 'a,
+    {
+        BoxedParser::new(self)
+    }
+
+    /// Execute another `Parser` after this one, returns both outputs as a tuple.
+    #[inline]
+    fn then<R>(self, parser: R) -> impl Parser<I, Output = (Self::Output, R::Output)>
+    where
+        R: Parser<I>,
+        Self: Sized,
+    {
+        Then(self, parser)
+    }
+
+    /// A short for [`then`](Parser::then).
+    #[inline]
+    fn and<R>(self, parser: R) -> impl Parser<I, Output = (Self::Output, R::Output)>
+    where
+        R: Parser<I>,
+        Self: Sized,
+    {
+        Then(self, parser)
+    }
+
+    /// Execute the `prefix` parser before this one and discard its output.
+    #[inline]
+    fn preceded<P>(self, prefix: P) -> impl Parser<I, Output = Self::Output>
+    where
+        P: Parser<I>,
+        Self: Sized,
+    {
+        Then(prefix, self).map(|(_, output)| output)
+    }
+
+    /// Execute the `suffix` parser after this one and discard its output.
+    #[inline]
+    fn terminated<S>(self, suffix: S) -> impl Parser<I, Output = Self::Output>
+    where
+        S: Parser<I>,
+        Self: Sized,
+    {
+        Then(self, suffix).map(|(output, _)| output)
+    }
+
+    /// Execute this parser between `start` and `end` parsers, only the output of this one is kept.
+    #[inline]
+    fn delimited<S, E>(self, start: S, end: E) -> impl Parser<I, Output = Self::Output>
+    where
+        S: Parser<I>,
+        E: Parser<I>,
+        Self: Sized,
+    {
+        self.preceded(start).terminated(end)
+    }
+
+    /// Execute this parser, the separator `sep` and the `right` parser in order,
+    /// returns the outputs of this one and `right` as a tuple.
+    #[inline]
+    fn separated_pair<S, R>(
+        self,
+        sep: S,
+        right: R,
+    ) -> impl Parser<I, Output = (Self::Output, R::Output)>
+    where
+        S: Parser<I>,
+        R: Parser<I>,
+        Self: Sized,
+    {
+        Then(self.terminated(sep), right)
+    }
+
+    /// On success, returns the provided `value` instead of the origin output.
+    #[inline]
+    fn value<O>(self, value: O) -> impl Parser<I, Output = O>
+    where
+        O: Clone,
+        Self: Sized,
+    {
+        self.map(move |_| value.clone())
+    }
+
+    /// On success, use fallible func `F` to convert origin output to type `O`.
+    #[inline]
+    fn and_then<F, O>(self, f: F) -> impl Parser<I, Output = O>
+    where
+        F: FnMut(Self::Output) -> Result<O, I::Error>,
+        Self: Sized,
+    {
+        AndThen(self, f)
+    }
+
+    /// On success, check the output with func `F`.
+    ///
+    /// If `F` returns false, returns a [`recovable`](ControlFlow::Recovable) error
+    /// that points to the input consumed by this parser.
+    #[inline]
+    fn verify<F>(self, f: F) -> impl Parser<I, Output = Self::Output>
+    where
+        F: FnMut(&Self::Output) -> bool,
+        Self: Sized,
+    {
+        Verify(self, f)
+    }
+
+    /// Report `name` as expected at the start position if this parser fails.
+    ///
+    /// Names are collected by a [`Furthest`](crate::Furthest) input to build "expected ..." errors.
+    #[inline]
+    fn label(self, name: &'static str) -> impl Parser<I, Output = Self::Output>
+    where
+        I: Clone,
+        Self: Sized,
+    {
+        Label(self, name)
+    }
+
+    /// Push a context frame `label` onto the error if this parser fails.
+    ///
+    /// See [`ParseError::contexts`] to read the frames back.
+    #[inline]
+    fn context(self, label: &'static str) -> impl Parser<I, Output = Self::Output>
+    where
+        Self: Sized,
+    {
+        Context(self, label)
+    }
+
+    /// Attach the span of the consumed input to the output of this parser.
+    #[inline]
+    fn spanned(self) -> impl Parser<I, Output = Spanned<Self::Output>>
+    where
+        Self: Sized,
+    {
+        SpannedParser(self)
+    }
+
+    /// Repeats this parser until it returns a `non-fatal` error, collects the outputs into a `Vec`.
+    #[inline]
+    fn many0(self) -> impl Parser<I, Output = Vec<Self::Output>>
+    where
+        I: Clone,
+        Self: Sized,
+    {
+        self.repeat(..)
+    }
+
+    /// Like [`many0`](Parser::many0), but this parser must succeed at least once.
+    #[inline]
+    fn many1(self) -> impl Parser<I, Output = Vec<Self::Output>>
+    where
+        I: Clone,
+        Self: Sized,
+    {
+        self.repeat(1..)
+    }
+
+    /// Repeats this parser up to the upper bound of `range`, collects the outputs into a `Vec`.
+    ///
+    /// If this parser succeeds fewer times than the lower bound of `range`, returns a [`Kind::Repeat`] error.
+    /// Once the lower bound is reached, a success that consumes no input ends the repetition.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is empty, e.g. `5..3`.
+    #[inline]
+    fn repeat<R>(self, range: R) -> impl Parser<I, Output = Vec<Self::Output>>
+    where
+        I: Clone,
+        R: RangeBounds<usize>,
+        Self: Sized,
+    {
+        self.fold_many(range, Vec::new, |mut elms, elm| {
+            elms.push(elm);
+            elms
+        })
+    }
+
+    /// Repeats this parser exactly `n` times, collects the outputs into a `Vec`.
+    #[inline]
+    fn count(self, n: usize) -> impl Parser<I, Output = Vec<Self::Output>>
+    where
+        I: Clone,
+        Self: Sized,
+    {
+        self.repeat(n..=n)
+    }
+
+    /// Repeats this parser like [`repeat`](Parser::repeat), but folds the outputs with func `F`
+    /// instead of collecting them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is empty, e.g. `5..3`.
+    #[inline]
+    fn fold_many<R, Init, F, Acc>(self, range: R, init: Init, f: F) -> impl Parser<I, Output = Acc>
+    where
+        I: Clone,
+        R: RangeBounds<usize>,
+        Init: FnMut() -> Acc,
+        F: FnMut(Acc, Self::Output) -> Acc,
+        Self: Sized,
+    {
+        FoldMany::new(self, range, init, f)
+    }
+
+    /// Parses zero or more outputs of this parser separated by the `sep` parser.
+    ///
+    /// A trailing separator is not consumed.
+    #[inline]
+    fn separated_list0<S>(self, sep: S) -> impl Parser<I, Output = Vec<Self::Output>>
+    where
+        I: Clone,
+        S: Parser<I>,
+        Self: Sized,
+    {
+        SeparatedList::new(self, sep, 0)
+    }
+
+    /// Like [`separated_list0`](Parser::separated_list0), but this parser must succeed at least once.
+    ///
+    /// Otherwise returns a [`Kind::SeparatedList`] error.
+    #[inline]
+    fn separated_list1<S>(self, sep: S) -> impl Parser<I, Output = Vec<Self::Output>>
+    where
+        I: Clone,
+        S: Parser<I>,
+        Self: Sized,
+    {
+        SeparatedList::new(self, sep, 1)
+    }
+
+    /// Recover from a `fatal` error by skipping input until `sync` matches, `sync` is not consumed.
+    ///
+    /// Only recovers when the input is a [`Recovery`](crate::Recovery) wrapper,
+    /// the error is collected and a [`Recovered::Error`] node is returned.
+    /// The skipped region covers at least one item, at the end of input the error is returned.
+    #[inline]
+    fn recover_until<S>(self, sync: S) -> impl Parser<I, Output = Recovered<Self::Output>>
+    where
+        I: Clone,
+        S: Parser<I>,
+        Self: Sized,
+    {
+        Recover::new(self, sync, false)
+    }
+
+    /// Like [`recover_until`](Parser::recover_until), but also consumes the `delimiter`.
+    #[inline]
+    fn skip_to<S>(self, delimiter: S) -> impl Parser<I, Output = Recovered<Self::Output>>
+    where
+        I: Clone,
+        S: Parser<I>,
+        Self: Sized,
+    {
+        Recover::new(self, delimiter, true)
+    }
 }
 
-/// Implement [`Parser`] for all `FnOnce(I) -> Result<O, I, E>`
+/// Implement [`Parser`] for all `FnMut(I) -> Result<O, I, E>`
 impl<O, I, F> Parser<I> for F
 where
     I: Input,
-    F: FnOnce(&mut I) -> Result<O, I::Error>,
+    F: FnMut(&mut I) -> Result<O, I::Error>,
 {
     type Output = O;
 
     #[inline]
-    fn parse(self, input: &mut I) -> Result<Self::Output, I::Error> {
+    fn parse(&mut self, input: &mut I) -> Result<Self::Output, I::Error> {
         self(input)
     }
 }
 
+struct ByRef<'a, P>(&'a mut P);
+
+impl<'a, P, I> Parser<I> for ByRef<'a, P>
+where
+    I: Input,
+    P: Parser<I>,
+{
+    type Output = P::Output;
+
+    #[inline]
+    fn parse(&mut self, input: &mut I) -> Result<Self::Output, I::Error> {
+        self.0.parse(input)
+    }
+}
+
 struct IsOk<P>(P);
 
 impl<P, I> Parser<I> for IsOk<P>
@@ -89,13 +389,17 @@ where
     type Output = Option<P::Output>;
 
     #[inline]
-    fn parse(self, input: &mut I) -> Result<Self::Output, I::Error> {
+    fn parse(&mut self, input: &mut I) -> Result<Self::Output, I::Error> {
         let snapshot = input.clone();
 
         // for retrospective analysis, we clone the input stream.
         match self.0.parse(input) {
             Ok(t) => Ok(Some(t)),
             Err(err) if err.control_flow() == ControlFlow::Fatal => Err(err),
+            // the end of a partial input decides nothing, more data is needed.
+            Err(err) if err.control_flow() == ControlFlow::Incomplete && snapshot.is_partial() => {
+                Err(err)
+            }
             Err(_) => {
                 *input = snapshot;
                 Ok(None)
@@ -110,12 +414,12 @@ impl<P, I, F, O> Parser<I> for Map<P, F>
 where
     I: Input,
     P: Parser<I>,
-    F: FnOnce(P::Output) -> O,
+    F: FnMut(P::Output) -> O,
 {
     type Output = O;
 
     #[inline]
-    fn parse(self, input: &mut I) -> Result<Self::Output, I::Error> {
+    fn parse(&mut self, input: &mut I) -> Result<Self::Output, I::Error> {
         self.0.parse(input).map(|output| (self.1)(output))
     }
 }
@@ -130,8 +434,11 @@ where
     type Output = P::Output;
 
     #[inline]
-    fn parse(self, input: &mut I) -> Result<Self::Output, I::Error> {
+    fn parse(&mut self, input: &mut I) -> Result<Self::Output, I::Error> {
         match self.0.parse(input) {
+            Err(err) if err.control_flow() == ControlFlow::Incomplete && input.is_partial() => {
+                Err(err)
+            }
             Err(err) => Err(err.into_fatal()),
             r => r,
         }
@@ -149,9 +456,9 @@ where
     type Output = O;
 
     #[inline]
-    fn parse(self, input: &mut I) -> Result<Self::Output, I::Error> {
+    fn parse(&mut self, input: &mut I) -> Result<Self::Output, I::Error> {
         let mut try_input = input.clone();
-        if let Some(v) = self.0.ok().parse(&mut try_input)? {
+        if let Some(v) = self.0.by_ref().ok().parse(&mut try_input)? {
             *input = try_input;
             return Ok(v);
         }
@@ -159,3 +466,251 @@ where
         self.1.parse(input)
     }
 }
+
+struct Then<L, R>(L, R);
+
+impl<L, R, I> Parser<I> for Then<L, R>
+where
+    I: Input,
+    L: Parser<I>,
+    R: Parser<I>,
+{
+    type Output = (L::Output, R::Output);
+
+    #[inline]
+    fn parse(&mut self, input: &mut I) -> Result<Self::Output, I::Error> {
+        let first = self.0.parse(input)?;
+        let second = self.1.parse(input)?;
+
+        Ok((first, second))
+    }
+}
+
+struct AndThen<P, F>(P, F);
+
+impl<P, I, F, O> Parser<I> for AndThen<P, F>
+where
+    I: Input,
+    P: Parser<I>,
+    F: FnMut(P::Output) -> Result<O, I::Error>,
+{
+    type Output = O;
+
+    #[inline]
+    fn parse(&mut self, input: &mut I) -> Result<Self::Output, I::Error> {
+        self.0.parse(input).and_then(&mut self.1)
+    }
+}
+
+struct Verify<P, F>(P, F);
+
+impl<P, I, F> Parser<I> for Verify<P, F>
+where
+    I: Input,
+    P: Parser<I>,
+    F: FnMut(&P::Output) -> bool,
+{
+    type Output = P::Output;
+
+    #[inline]
+    fn parse(&mut self, input: &mut I) -> Result<Self::Output, I::Error> {
+        let start = input.start();
+
+        let output = self.0.parse(input)?;
+
+        if !(self.1)(&output) {
+            return Err(
+                Kind::Verify(ControlFlow::Recovable, Span::Range(start..input.start())).into(),
+            );
+        }
+
+        Ok(output)
+    }
+}
+
+struct Label<P>(P, &'static str);
+
+impl<P, I> Parser<I> for Label<P>
+where
+    I: Input + Clone,
+    P: Parser<I>,
+{
+    type Output = P::Output;
+
+    #[inline]
+    fn parse(&mut self, input: &mut I) -> Result<Self::Output, I::Error> {
+        let start = input.clone();
+
+        self.0
+            .parse(input)
+            .inspect_err(|_| start.report_expected(self.1))
+    }
+}
+
+struct Context<P>(P, &'static str);
+
+impl<P, I> Parser<I> for Context<P>
+where
+    I: Input,
+    P: Parser<I>,
+{
+    type Output = P::Output;
+
+    #[inline]
+    fn parse(&mut self, input: &mut I) -> Result<Self::Output, I::Error> {
+        self.0.parse(input).map_err(|err| err.context(self.1))
+    }
+}
+
+struct SpannedParser<P>(P);
+
+impl<P, I> Parser<I> for SpannedParser<P>
+where
+    I: Input,
+    P: Parser<I>,
+{
+    type Output = Spanned<P::Output>;
+
+    #[inline]
+    fn parse(&mut self, input: &mut I) -> Result<Self::Output, I::Error> {
+        let start = input.start();
+        let value = self.0.parse(input)?;
+
+        Ok(Spanned::new(value, Span::Range(start..input.start())))
+    }
+}
+
+#[cfg(all(test, feature = "lang"))]
+mod tests {
+    use crate::{
+        ControlFlow, Input, Kind, ParseError, Parser, Span, Spanned, keyword, lang::TokenStream,
+        next, next_if,
+    };
+
+    type TS<'a> = TokenStream<'a, Kind>;
+
+    #[test]
+    fn test_then() {
+        let mut input = TS::from("ab");
+        assert_eq!(
+            next(b'a').then(next(b'b')).parse(&mut input),
+            Ok((TS::from("a"), TS::from((1, "b"))))
+        );
+        assert!(input.value.is_empty());
+
+        assert_eq!(
+            next(b'a').and(next(b'b')).parse(&mut TS::from("ac")),
+            Err(Kind::Next(ControlFlow::Recovable, Span::Range(1..2)))
+        );
+
+        assert_eq!(
+            next(b'a')
+                .then(next(b'b').fatal())
+                .ok()
+                .parse(&mut TS::from("ac")),
+            Err(Kind::Next(ControlFlow::Fatal, Span::Range(1..2)))
+        );
+    }
+
+    #[test]
+    fn test_reuse() {
+        let mut input = TS::from("a,a");
+        let mut a = next(b'a').map(|input: TS<'_>| input.start());
+        let mut comma = next(b',');
+
+        assert_eq!(a.parse(&mut input), Ok(0));
+        assert!(comma.parse(&mut input).is_ok());
+        assert_eq!(a.by_ref().ok().parse(&mut input), Ok(Some(2)));
+        assert_eq!(a.by_ref().ok().parse(&mut input), Ok(None));
+    }
+
+    #[test]
+    fn test_delimited() {
+        assert_eq!(
+            keyword("fn")
+                .preceded(next(b'('))
+                .terminated(next(b')'))
+                .parse(&mut TS::from("(fn)")),
+            Ok(TS::from((1, "fn")))
+        );
+
+        assert_eq!(
+            keyword("fn")
+                .delimited(next(b'('), next(b')'))
+                .parse(&mut TS::from("(fn)")),
+            Ok(TS::from((1, "fn")))
+        );
+
+        assert_eq!(
+            keyword("a")
+                .separated_pair(next(b','), keyword("b"))
+                .parse(&mut TS::from("a,b")),
+            Ok((TS::from("a"), TS::from((2, "b"))))
+        );
+    }
+
+    #[test]
+    fn test_value_and_then_verify() {
+        assert_eq!(
+            keyword("true").value(true).parse(&mut TS::from("true")),
+            Ok(true)
+        );
+
+        assert_eq!(
+            keyword("1")
+                .and_then(|input: TS<'_>| Ok::<_, Kind>(input.value.len()))
+                .parse(&mut TS::from("1")),
+            Ok(1)
+        );
+
+        let mut input = TS::from("ab");
+        assert_eq!(
+            keyword("ab")
+                .verify(|input: &TS<'_>| input.value == "cd")
+                .ok()
+                .parse(&mut input),
+            Ok(None)
+        );
+        assert_eq!(input, TS::from("ab"));
+
+        assert_eq!(
+            keyword("ab")
+                .verify(|input: &TS<'_>| input.value == "cd")
+                .parse(&mut TS::from("ab")),
+            Err(Kind::Verify(ControlFlow::Recovable, Span::Range(0..2)))
+        );
+    }
+
+    #[test]
+    fn test_context() {
+        let mut signature = keyword("fn")
+            .then(keyword("(").context("parameters"))
+            .context("function signature");
+
+        let err = signature.parse(&mut TS::from("fn)")).unwrap_err();
+
+        assert_eq!(err.contexts(), vec!["function signature", "parameters"]);
+        assert_eq!(err.control_flow(), ControlFlow::Recovable);
+        assert_eq!(err.to_span(), Span::Range(2..3));
+        assert_eq!(
+            err.into_fatal().root(),
+            &Kind::Keyword(ControlFlow::Fatal, Span::Range(2..3))
+        );
+    }
+
+    #[test]
+    fn test_spanned() {
+        let mut input = TS::from((10, "42;"));
+
+        let number = next_if(|c: u8| c.is_ascii_digit())
+            .many1()
+            .map(|digits| digits.len() as u64)
+            .spanned()
+            .parse(&mut input)
+            .unwrap();
+
+        assert_eq!(number, Spanned::new(2, Span::Range(10..12)));
+        assert_eq!(*number + 1, 3);
+        assert_eq!(input, TS::from((12, ";")));
+    }
+}
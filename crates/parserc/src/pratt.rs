@@ -0,0 +1,340 @@
+//! Pratt (precedence-climbing) parser for expressions.
+//!
+//! Operators are syntax types implementing [`Operator`], usually derived with `#[syntax(precedence)]`:
+//!
+//! ```
+//! use parserc::{
+//!     Kind,
+//!     lang::{LangInput, TokenStream},
+//!     pratt::Expr,
+//!     syntax::{Byte, InputSyntaxExt, Syntax},
+//! };
+//!
+//! #[derive(Debug, Syntax)]
+//! #[syntax(token = |c: u8| c.is_ascii_digit())]
+//! struct Number<I>(I)
+//! where
+//!     I: LangInput;
+//!
+//! #[derive(Debug, Syntax)]
+//! #[syntax(precedence)]
+//! enum BinOp<I>
+//! where
+//!     I: LangInput,
+//! {
+//!     #[syntax(infix = 1)]
+//!     Add(Byte<I, b'+'>),
+//!     #[syntax(infix = 2)]
+//!     Mul(Byte<I, b'*'>),
+//!     #[syntax(infix = 3, assoc = right)]
+//!     Pow(Byte<I, b'^'>),
+//! }
+//!
+//! // no prefix and postfix operators.
+//! type Arith<I> = Expr<Number<I>, BinOp<I>>;
+//!
+//! let expr = TokenStream::<'_, Kind>::from("1+2*3^4")
+//!     .parse::<Arith<_>>()
+//!     .unwrap();
+//!
+//! // `*` and `^` bind tighter than `+`.
+//! assert!(matches!(expr, Expr::Infix(_, BinOp::Add(_), _)));
+//! ```
+
+use crate::{
+    ControlFlow, Kind, Span,
+    input::Input,
+    parser::Parser,
+    syntax::{InputSyntaxExt, Syntax},
+};
+
+/// Associativity of an infix operator.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Assoc {
+    /// `a + b + c` is parsed as `(a + b) + c`.
+    Left,
+    /// `a ^ b ^ c` is parsed as `a ^ (b ^ c)`.
+    Right,
+}
+
+/// An operator of pratt expressions.
+///
+/// A higher binding power binds tighter, on a tie a prefix operator binds tighter than
+/// a left-associative infix operator or a postfix operator.
+///
+/// Each func returns `None` if the operator can't be used in that position.
+pub trait Operator<I>: Syntax<I>
+where
+    I: Input,
+{
+    /// Returns the binding power when used as a prefix operator.
+    #[inline]
+    fn prefix(&self) -> Option<u8> {
+        None
+    }
+
+    /// Returns the binding power and associativity when used as an infix operator.
+    #[inline]
+    fn infix(&self) -> Option<(u8, Assoc)> {
+        None
+    }
+
+    /// Returns the binding power when used as a postfix operator.
+    #[inline]
+    fn postfix(&self) -> Option<u8> {
+        None
+    }
+}
+
+/// A placeholder operator that never matches, used for missing operator kinds of [`Expr`].
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NoOp {}
+
+impl<I> Syntax<I> for NoOp
+where
+    I: Input,
+{
+    #[inline]
+    fn parse(input: &mut I) -> Result<Self, I::Error> {
+        Err(Kind::Syntax("NoOp", ControlFlow::Recovable, input.to_span_at(0)).into())
+    }
+
+    #[inline]
+    fn to_span(&self) -> Span {
+        match *self {}
+    }
+}
+
+impl<I> Operator<I> for NoOp where I: Input {}
+
+/// Expression tree parsed by precedence climbing.
+///
+/// `A` is the atom syntax, `In`, `Pre` and `Post` are infix, prefix and postfix [`Operator`]s.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Expr<A, In, Pre = NoOp, Post = NoOp> {
+    /// An atom expression.
+    Atom(A),
+    /// A prefix operator and its operand.
+    Prefix(Pre, Box<Self>),
+    /// An infix operator and its operands.
+    Infix(Box<Self>, In, Box<Self>),
+    /// A postfix operator and its operand.
+    Postfix(Box<Self>, Post),
+}
+
+impl<I, A, In, Pre, Post> Syntax<I> for Expr<A, In, Pre, Post>
+where
+    I: Input + Clone,
+    A: Syntax<I>,
+    In: Operator<I>,
+    Pre: Operator<I>,
+    Post: Operator<I>,
+{
+    #[inline]
+    fn parse(input: &mut I) -> Result<Self, I::Error> {
+        parse_expr(input, 0)
+    }
+
+    fn to_span(&self) -> Span {
+        match self {
+            Expr::Atom(atom) => atom.to_span(),
+            Expr::Prefix(op, rhs) => op.to_span().union(&rhs.to_span()),
+            Expr::Infix(lhs, op, rhs) => lhs.to_span().union(&op.to_span()).union(&rhs.to_span()),
+            Expr::Postfix(lhs, op) => lhs.to_span().union(&op.to_span()),
+        }
+    }
+}
+
+/// Parses an operator `O` if it can be used in the position checked by `F`.
+///
+/// Returns the operator and its binding power, restores the input otherwise.
+fn parse_operator<I, O, F, P>(input: &mut I, f: F) -> Result<Option<(O, P)>, I::Error>
+where
+    I: Input + Clone,
+    O: Operator<I>,
+    F: FnOnce(&O) -> Option<P>,
+{
+    let snapshot = input.clone();
+
+    let Some(op) = O::into_parser().ok().parse(input)? else {
+        return Ok(None);
+    };
+
+    let Some(power) = f(&op) else {
+        *input = snapshot;
+        return Ok(None);
+    };
+
+    Ok(Some((op, power)))
+}
+
+fn parse_expr<I, A, In, Pre, Post>(
+    input: &mut I,
+    min_power: u16,
+) -> Result<Expr<A, In, Pre, Post>, I::Error>
+where
+    I: Input + Clone,
+    A: Syntax<I>,
+    In: Operator<I>,
+    Pre: Operator<I>,
+    Post: Operator<I>,
+{
+    // user binding power `p` is mapped to `2p` (left side) and `2p + 1` (right side).
+    let mut lhs = if let Some((op, power)) = parse_operator(input, Pre::prefix)? {
+        let rhs = parse_expr(input, u16::from(power) * 2 + 1)?;
+        Expr::Prefix(op, Box::new(rhs))
+    } else {
+        Expr::Atom(input.parse()?)
+    };
+
+    loop {
+        let snapshot = input.clone();
+
+        if let Some((op, power)) = parse_operator(input, Post::postfix)? {
+            if u16::from(power) * 2 < min_power {
+                *input = snapshot;
+                break;
+            }
+
+            lhs = Expr::Postfix(Box::new(lhs), op);
+            continue;
+        }
+
+        if let Some((op, (power, assoc))) = parse_operator(input, In::infix)? {
+            let (left, right) = match assoc {
+                Assoc::Left => (u16::from(power) * 2, u16::from(power) * 2 + 1),
+                Assoc::Right => (u16::from(power) * 2 + 1, u16::from(power) * 2),
+            };
+
+            if left < min_power {
+                *input = snapshot;
+                break;
+            }
+
+            let rhs = parse_expr(input, right)?;
+            lhs = Expr::Infix(Box::new(lhs), op, Box::new(rhs));
+            continue;
+        }
+
+        break;
+    }
+
+    Ok(lhs)
+}
+
+#[cfg(all(test, feature = "lang"))]
+mod tests {
+    use crate::{
+        ControlFlow, Input, Kind, Parser, Span,
+        lang::TokenStream,
+        next_if,
+        syntax::{InputSyntaxExt, Syntax},
+    };
+
+    use super::{Assoc, Expr, Operator};
+
+    type TS<'a> = TokenStream<'a, Kind>;
+
+    /// A digit atom.
+    #[derive(Debug, PartialEq, Clone)]
+    struct Num(u8);
+
+    impl<'a> Syntax<TS<'a>> for Num {
+        fn parse(input: &mut TS<'a>) -> Result<Self, Kind> {
+            next_if(|c: u8| c.is_ascii_digit())
+                .map(|digit: TS<'a>| Num(digit.iter().next().unwrap() - b'0'))
+                .parse(input)
+        }
+
+        fn to_span(&self) -> Span {
+            Span::None
+        }
+    }
+
+    /// Operators by their char, `-` is both a prefix and an infix operator.
+    #[derive(Debug, PartialEq, Clone)]
+    struct Op(u8);
+
+    impl<'a> Syntax<TS<'a>> for Op {
+        fn parse(input: &mut TS<'a>) -> Result<Self, Kind> {
+            next_if(|c: u8| b"+-*^!".contains(&c))
+                .map(|op: TS<'a>| Op(op.iter().next().unwrap()))
+                .parse(input)
+        }
+
+        fn to_span(&self) -> Span {
+            Span::None
+        }
+    }
+
+    impl<'a> Operator<TS<'a>> for Op {
+        fn prefix(&self) -> Option<u8> {
+            (self.0 == b'-').then_some(4)
+        }
+
+        fn infix(&self) -> Option<(u8, Assoc)> {
+            match self.0 {
+                b'+' | b'-' => Some((1, Assoc::Left)),
+                b'*' => Some((2, Assoc::Left)),
+                b'^' => Some((3, Assoc::Right)),
+                _ => None,
+            }
+        }
+
+        fn postfix(&self) -> Option<u8> {
+            (self.0 == b'!').then_some(5)
+        }
+    }
+
+    type Arith = Expr<Num, Op, Op, Op>;
+
+    /// Parses `source` and prints the tree with parentheses.
+    fn parse(source: &str) -> String {
+        fn show(expr: &Arith) -> String {
+            match expr {
+                Expr::Atom(num) => num.0.to_string(),
+                Expr::Prefix(op, rhs) => format!("({}{})", op.0 as char, show(rhs)),
+                Expr::Infix(lhs, op, rhs) => {
+                    format!("({}{}{})", show(lhs), op.0 as char, show(rhs))
+                }
+                Expr::Postfix(lhs, op) => format!("({}{})", show(lhs), op.0 as char),
+            }
+        }
+
+        let mut input = TS::from(source);
+        let expr = input.parse::<Arith>().unwrap();
+        assert!(input.is_empty(), "{}", source);
+
+        show(&expr)
+    }
+
+    #[test]
+    fn test_assoc() {
+        assert_eq!(parse("1-2-3"), "((1-2)-3)");
+        assert_eq!(parse("2^3^4"), "(2^(3^4))");
+    }
+
+    #[test]
+    fn test_precedence() {
+        assert_eq!(parse("1+2*3-4"), "((1+(2*3))-4)");
+        assert_eq!(parse("1*2^3*4"), "((1*(2^3))*4)");
+        assert_eq!(parse("1*-2+3"), "((1*(-2))+3)");
+    }
+
+    #[test]
+    fn test_prefix_postfix() {
+        // `!` binds tighter than `-`, which binds tighter than `^`.
+        assert_eq!(parse("-3!"), "(-(3!))");
+        assert_eq!(parse("-2^2"), "((-2)^2)");
+        assert_eq!(parse("--1!!"), "(-(-((1!)!)))");
+        assert_eq!(parse("2*3!"), "(2*(3!))");
+
+        assert_eq!(
+            TS::from("1+").parse::<Arith>(),
+            Err(Kind::NextIf(ControlFlow::Incomplete, Span::Range(2..2)))
+        );
+    }
+}
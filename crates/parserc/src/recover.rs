@@ -0,0 +1,519 @@
+//! Error recovery for parsers that report more than one error.
+
+use std::{
+    cell::RefCell,
+    fmt::{Debug, Display},
+    rc::Rc,
+};
+
+use crate::{
+    ControlFlow, ParseError, Span,
+    input::{AsBytes, AsStr, Find, Input, Item, StartWith},
+    memo::MemoTable,
+    parser::Parser,
+    trivia::SkipTrivia,
+};
+
+/// A syntax node that is either parsed successfully or skipped by error recovery.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Recovered<T> {
+    /// The node is parsed successfully.
+    Ok(T),
+    /// The node failed with a fatal error, and the input region is skipped.
+    Error(Span),
+}
+
+impl<T> Recovered<T> {
+    /// Returns true if this node is an error node.
+    #[inline]
+    pub fn is_error(&self) -> bool {
+        matches!(self, Self::Error(_))
+    }
+
+    /// Converts to `Option<T>`, discards the error span.
+    #[inline]
+    pub fn ok(self) -> Option<T> {
+        match self {
+            Self::Ok(value) => Some(value),
+            Self::Error(_) => None,
+        }
+    }
+}
+
+/// An `Input` wrapper that collects recovered errors instead of aborting on the first one.
+///
+/// [`recover_until`](Parser::recover_until), [`skip_to`](Parser::skip_to) and
+/// [`Delimiter`](crate::syntax::Delimiter) only recover when the input is wrapped by `Recovery`,
+/// otherwise fatal errors are returned as usual.
+///
+/// The collected errors are part of the input state: a branch that is backtracked
+/// (e.g. by [`ok`](Parser::ok), [`or`](Parser::or) or [`alt`](crate::alt)) drops the errors it recovered.
+pub struct Recovery<I>
+where
+    I: Input,
+{
+    input: I,
+    /// Every recovered error, shared by the clones of this input.
+    errors: Rc<RefCell<Vec<Option<I::Error>>>>,
+    /// The errors recovered by this input state.
+    recorded: RefCell<Option<Rc<Recorded>>>,
+}
+
+/// A persistent list of indices into [`Recovery::errors`], cloning an input shares it.
+struct Recorded {
+    index: usize,
+    len: usize,
+    prev: Option<Rc<Recorded>>,
+}
+
+impl<I> Recovery<I>
+where
+    I: Input,
+{
+    /// Wrap `input` with an empty error list.
+    #[inline]
+    pub fn new(input: I) -> Self {
+        Self {
+            input,
+            errors: Default::default(),
+            recorded: Default::default(),
+        }
+    }
+
+    /// Returns the wrapped input.
+    #[inline]
+    pub fn as_inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Consumes this wrapper and returns the wrapped input.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    /// Returns the number of collected errors.
+    #[inline]
+    pub fn errors_len(&self) -> usize {
+        self.recorded
+            .borrow()
+            .as_ref()
+            .map_or(0, |recorded| recorded.len)
+    }
+
+    /// Takes all collected errors, in the order they were reported.
+    pub fn take_errors(&self) -> Vec<I::Error> {
+        let mut indices = vec![];
+        let mut next = self.recorded.take();
+
+        while let Some(recorded) = next {
+            indices.push(recorded.index);
+            next = recorded.prev.clone();
+        }
+
+        let mut errors = self.errors.borrow_mut();
+
+        indices
+            .into_iter()
+            .rev()
+            .filter_map(|index| errors[index].take())
+            .collect()
+    }
+
+    /// Returns a wrapper of `input` that shares the errors of this one.
+    #[inline]
+    fn with_input(&self, input: I) -> Self {
+        Self {
+            input,
+            errors: self.errors.clone(),
+            recorded: RefCell::new(self.recorded.borrow().clone()),
+        }
+    }
+}
+
+impl<I> From<I> for Recovery<I>
+where
+    I: Input,
+{
+    fn from(value: I) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<I> Clone for Recovery<I>
+where
+    I: Input + Clone,
+{
+    fn clone(&self) -> Self {
+        self.with_input(self.input.clone())
+    }
+}
+
+impl<I> Debug for Recovery<I>
+where
+    I: Input,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Recovery").field(&self.input).finish()
+    }
+}
+
+impl<I> PartialEq for Recovery<I>
+where
+    I: Input,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.input == other.input
+    }
+}
+
+impl<I> Input for Recovery<I>
+where
+    I: Input,
+{
+    type Item = I::Item;
+
+    type Error = I::Error;
+
+    type Iter = I::Iter;
+
+    type IterIndices = I::IterIndices;
+
+    const HAS_TRIVIA: bool = I::HAS_TRIVIA;
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.input.len()
+    }
+
+    #[inline]
+    fn split_to(&mut self, at: usize) -> Self {
+        let input = self.input.split_to(at);
+        self.with_input(input)
+    }
+
+    #[inline]
+    fn split_off(&mut self, at: usize) -> Self {
+        let input = self.input.split_off(at);
+        self.with_input(input)
+    }
+
+    #[inline]
+    fn iter(&self) -> Self::Iter {
+        self.input.iter()
+    }
+
+    #[inline]
+    fn iter_indices(&self) -> Self::IterIndices {
+        self.input.iter_indices()
+    }
+
+    #[inline]
+    fn start(&self) -> usize {
+        self.input.start()
+    }
+
+    #[inline]
+    fn end(&self) -> usize {
+        self.input.end()
+    }
+
+    #[inline]
+    fn memo_table(&self) -> Option<&MemoTable> {
+        self.input.memo_table()
+    }
+
+    #[inline]
+    fn is_partial(&self) -> bool {
+        self.input.is_partial()
+    }
+
+    #[inline]
+    fn skip_trivia(&mut self) -> Result<(), Self::Error> {
+        self.input.skip_trivia()
+    }
+
+    #[inline]
+    fn replace_trivia(&mut self, _skip: Option<SkipTrivia<Self>>) -> Option<SkipTrivia<Self>> {
+        crate::trivia::assert_outermost::<I>();
+        None
+    }
+
+    #[inline]
+    fn recover(&self, error: Self::Error) -> Result<(), Self::Error> {
+        let mut errors = self.errors.borrow_mut();
+        let prev = self.recorded.take();
+
+        self.recorded.replace(Some(Rc::new(Recorded {
+            index: errors.len(),
+            len: prev.as_ref().map_or(0, |prev| prev.len) + 1,
+            prev,
+        })));
+
+        errors.push(Some(error));
+
+        Ok(())
+    }
+
+    #[inline]
+    fn report_expected(&self, name: impl Display) {
+        self.input.report_expected(name);
+    }
+}
+
+impl<I> AsBytes for Recovery<I>
+where
+    I: Input + AsBytes,
+{
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        self.input.as_bytes()
+    }
+}
+
+impl<I> AsStr for Recovery<I>
+where
+    I: Input + AsStr,
+{
+    #[inline]
+    fn as_str(&self) -> &str {
+        self.input.as_str()
+    }
+}
+
+impl<I, N> StartWith<N> for Recovery<I>
+where
+    I: Input + StartWith<N>,
+{
+    #[inline]
+    fn starts_with(&self, needle: N) -> Option<usize> {
+        self.input.starts_with(needle)
+    }
+
+    #[inline]
+    fn needed(&self, needle: N) -> Option<usize> {
+        self.input.needed(needle)
+    }
+}
+
+impl<I, N> Find<N> for Recovery<I>
+where
+    I: Input + Find<N>,
+{
+    #[inline]
+    fn find(&self, needle: N) -> Option<usize> {
+        self.input.find(needle)
+    }
+}
+
+#[cfg(feature = "lang")]
+impl<I> crate::lang::LangInput for Recovery<I> where I: crate::lang::LangInput {}
+
+/// Skip items of `input` until `sync` matches or the input is exhausted.
+///
+/// The `sync` syntax itself is not consumed.
+pub(crate) fn skip_until<I, S>(input: &mut I, mut sync: S) -> Result<(), I::Error>
+where
+    I: Input + Clone,
+    S: Parser<I>,
+{
+    loop {
+        let mut lookahead = input.clone();
+
+        if sync.by_ref().ok().parse(&mut lookahead)?.is_some() {
+            return Ok(());
+        }
+
+        let Some(item) = input.iter().next() else {
+            return Ok(());
+        };
+
+        input.split_to(item.len());
+    }
+}
+
+/// Parser returns by [`Parser::recover_until`] and [`Parser::skip_to`].
+pub(crate) struct Recover<P, S> {
+    parser: P,
+    sync: S,
+    /// consumes the `sync` syntax after skipping.
+    consume: bool,
+}
+
+impl<P, S> Recover<P, S> {
+    pub(crate) fn new(parser: P, sync: S, consume: bool) -> Self {
+        Self {
+            parser,
+            sync,
+            consume,
+        }
+    }
+}
+
+impl<P, S, I> Parser<I> for Recover<P, S>
+where
+    I: Input + Clone,
+    P: Parser<I>,
+    S: Parser<I>,
+{
+    type Output = Recovered<P::Output>;
+
+    fn parse(&mut self, input: &mut I) -> Result<Self::Output, I::Error> {
+        let snapshot = input.clone();
+
+        let err = match self.parser.parse(input) {
+            Ok(output) => return Ok(Recovered::Ok(output)),
+            Err(err) if err.control_flow() == ControlFlow::Fatal => err,
+            Err(err) => return Err(err),
+        };
+
+        // skip from the start of the failed syntax, the failed parser may leave input anywhere,
+        // the errors recovered inside it are dropped too.
+        *input = snapshot;
+
+        // an error node covers at least one item, so a repetition of it always makes progress.
+        if input.is_empty() {
+            return Err(err);
+        }
+
+        input.recover(err)?;
+
+        let start = input.start();
+
+        loop {
+            skip_until(input, self.sync.by_ref())?;
+
+            if self.consume {
+                self.sync.by_ref().ok().parse(input)?;
+            }
+
+            if input.start() != start {
+                return Ok(Recovered::Error(Span::Range(start..input.start())));
+            }
+
+            // the failed syntax starts at `sync`, skip its first item too.
+            let item = input.iter().next().expect("non-empty input");
+            input.split_to(item.len());
+        }
+    }
+}
+
+#[cfg(all(test, feature = "lang"))]
+mod tests {
+    use crate::{
+        ControlFlow, Input, Kind, Parser, Span, alt_longest, keyword, lang::TokenStream, next,
+    };
+
+    use super::{Recovered, Recovery};
+
+    type TS<'a> = TokenStream<'a, Kind>;
+
+    fn stmt<I>(input: &mut I) -> Result<(), Kind>
+    where
+        I: Input<Item = u8, Error = Kind> + crate::StartWith<&'static str> + Clone,
+    {
+        keyword("a").then(next(b';').fatal()).value(()).parse(input)
+    }
+
+    #[test]
+    fn test_skip_to() {
+        let mut input = Recovery::new(TS::from("a;ab;a;"));
+
+        assert_eq!(
+            stmt.skip_to(next(b';')).many0().parse(&mut input),
+            Ok(vec![
+                Recovered::Ok(()),
+                Recovered::Error(Span::Range(2..5)),
+                Recovered::Ok(())
+            ])
+        );
+
+        assert!(input.is_empty());
+        assert_eq!(
+            input.take_errors(),
+            vec![Kind::Next(ControlFlow::Fatal, Span::Range(3..4))]
+        );
+
+        assert_eq!(
+            stmt.skip_to(next(b';'))
+                .many0()
+                .parse(&mut TS::from("a;ab;a;")),
+            Err(Kind::Next(ControlFlow::Fatal, Span::Range(3..4)))
+        );
+    }
+
+    #[test]
+    fn test_recover_until() {
+        let mut input = Recovery::new(TS::from("abc;"));
+
+        assert_eq!(
+            stmt.recover_until(next(b';')).parse(&mut input),
+            Ok(Recovered::Error(Span::Range(0..3)))
+        );
+        assert_eq!(input.as_inner(), &TS::from((3, ";")));
+        assert_eq!(input.errors_len(), 1);
+
+        // the skipped region is never empty.
+        let mut input = Recovery::new(TS::from(";;"));
+        let mut parser = keyword("a").fatal().recover_until(next(b';'));
+
+        assert_eq!(
+            parser.parse(&mut input),
+            Ok(Recovered::Error(Span::Range(0..1)))
+        );
+        assert_eq!(
+            parser.parse(&mut input),
+            Ok(Recovered::Error(Span::Range(1..2)))
+        );
+        assert_eq!(
+            parser.parse(&mut input),
+            Err(Kind::Keyword(ControlFlow::Fatal, Span::Range(2..2)))
+        );
+        assert_eq!(input.errors_len(), 2);
+
+        // non-fatal errors are not recovered.
+        assert_eq!(
+            stmt.recover_until(next(b';'))
+                .parse(&mut Recovery::new(TS::from("b;"))),
+            Err(Kind::Keyword(ControlFlow::Recovable, Span::Range(0..1)))
+        );
+    }
+
+    #[test]
+    fn test_backtrack() {
+        // the first branch recovers `b`, then fails and is backtracked.
+        let mut input = Recovery::new(TS::from("ab;a;"));
+
+        assert_eq!(
+            stmt.skip_to(next(b';'))
+                .then(next(b'!'))
+                .value(1)
+                .or(keyword("ab;").value(2))
+                .parse(&mut input),
+            Ok(2)
+        );
+        assert_eq!(input.errors_len(), 0);
+        assert!(input.take_errors().is_empty());
+
+        // only the errors of the longest branch are kept.
+        let mut input = Recovery::new(TS::from("ab;ab;"));
+
+        assert_eq!(
+            alt_longest((
+                stmt.skip_to(next(b';')).value(1),
+                stmt.skip_to(next(b';')).many0().value(2),
+            ))
+            .parse(&mut input),
+            Ok(2)
+        );
+        assert!(input.is_empty());
+        assert_eq!(
+            input.take_errors(),
+            vec![
+                Kind::Next(ControlFlow::Fatal, Span::Range(1..2)),
+                Kind::Next(ControlFlow::Fatal, Span::Range(4..5))
+            ]
+        );
+    }
+}
@@ -0,0 +1,304 @@
+//! Repetition parser combinators.
+
+use std::ops::{Bound, RangeBounds};
+
+use crate::{
+    Span,
+    errors::{ControlFlow, Kind},
+    input::Input,
+    parser::Parser,
+};
+
+/// Parser returns by [`Parser::fold_many`].
+pub(crate) struct FoldMany<P, Init, F> {
+    parser: P,
+    /// minimum number of repetitions.
+    min: usize,
+    /// maximum number of repetitions, `None` means unbounded.
+    max: Option<usize>,
+    init: Init,
+    f: F,
+}
+
+impl<P, Init, F> FoldMany<P, Init, F> {
+    pub(crate) fn new<R>(parser: P, range: R, init: Init, f: F) -> Self
+    where
+        R: RangeBounds<usize>,
+    {
+        let min = match range.start_bound() {
+            Bound::Included(n) => *n,
+            Bound::Excluded(n) => n.checked_add(1).expect("repeat: the range is empty"),
+            Bound::Unbounded => 0,
+        };
+
+        let max = match range.end_bound() {
+            Bound::Included(n) => Some(*n),
+            Bound::Excluded(n) => Some(n.checked_sub(1).expect("repeat: the range is empty")),
+            Bound::Unbounded => None,
+        };
+
+        if let Some(max) = max {
+            assert!(
+                min <= max,
+                "repeat: the range is empty, its lower bound {} is greater than its upper bound {}",
+                min,
+                max
+            );
+        }
+
+        Self {
+            parser,
+            min,
+            max,
+            init,
+            f,
+        }
+    }
+}
+
+impl<P, I, Init, F, Acc> Parser<I> for FoldMany<P, Init, F>
+where
+    I: Input + Clone,
+    P: Parser<I>,
+    Init: FnMut() -> Acc,
+    F: FnMut(Acc, P::Output) -> Acc,
+{
+    type Output = Acc;
+
+    fn parse(&mut self, input: &mut I) -> Result<Self::Output, I::Error> {
+        let start = input.start();
+        let mut acc = (self.init)();
+        let mut count = 0;
+
+        while self.max.is_none_or(|max| count < max) {
+            let iteration = input.start();
+
+            let Some(output) = self.parser.by_ref().ok().parse(input)? else {
+                break;
+            };
+
+            // an unbounded repetition would loop forever, a bounded one gains nothing
+            // from repeating a success that consumes nothing once its lower bound is reached.
+            if self.max.is_none() {
+                input.ensure_progress(iteration)?;
+            } else if count >= self.min && input.start() == iteration {
+                break;
+            }
+
+            acc = (self.f)(acc, output);
+            count += 1;
+        }
+
+        if count < self.min {
+            return Err(
+                Kind::Repeat(ControlFlow::Recovable, Span::Range(start..input.start())).into(),
+            );
+        }
+
+        Ok(acc)
+    }
+}
+
+/// Parser returns by [`Parser::separated_list0`] and [`Parser::separated_list1`].
+pub(crate) struct SeparatedList<P, S> {
+    parser: P,
+    sep: S,
+    /// minimum number of elements.
+    min: usize,
+}
+
+impl<P, S> SeparatedList<P, S> {
+    pub(crate) fn new(parser: P, sep: S, min: usize) -> Self {
+        Self { parser, sep, min }
+    }
+}
+
+impl<P, S, I> Parser<I> for SeparatedList<P, S>
+where
+    I: Input + Clone,
+    P: Parser<I>,
+    S: Parser<I>,
+{
+    type Output = Vec<P::Output>;
+
+    fn parse(&mut self, input: &mut I) -> Result<Self::Output, I::Error> {
+        let start = input.start();
+        let mut elms = vec![];
+
+        let first = self.parser.by_ref().ok().parse(input)?;
+
+        if let Some(elm) = first {
+            elms.push(elm);
+
+            loop {
+                let iteration = input.start();
+
+                let Some((_, elm)) = self
+                    .sep
+                    .by_ref()
+                    .then(self.parser.by_ref())
+                    .ok()
+                    .parse(input)?
+                else {
+                    break;
+                };
+
+                input.ensure_progress(iteration)?;
+
+                elms.push(elm);
+            }
+        }
+
+        if elms.len() < self.min {
+            return Err(Kind::SeparatedList(
+                ControlFlow::Recovable,
+                Span::Range(start..input.start()),
+            )
+            .into());
+        }
+
+        Ok(elms)
+    }
+}
+
+#[cfg(all(test, feature = "lang"))]
+mod tests {
+    use std::ops::Bound;
+
+    use crate::{ControlFlow, Kind, Parser, Span, keyword, lang::TokenStream, next};
+
+    type TS<'a> = TokenStream<'a, Kind>;
+
+    fn a(input: &mut TS<'_>) -> Result<u8, Kind> {
+        next(b'a').value(b'a').parse(input)
+    }
+
+    fn comma(input: &mut TS<'_>) -> Result<(), Kind> {
+        next(b',').value(()).parse(input)
+    }
+
+    #[test]
+    fn test_many() {
+        let mut input = TS::from("aaab");
+        assert_eq!(a.many0().parse(&mut input), Ok(vec![b'a'; 3]));
+        assert_eq!(input, TS::from((3, "b")));
+
+        assert_eq!(a.many0().parse(&mut TS::from("b")), Ok(vec![]));
+        assert_eq!(
+            a.many1().parse(&mut TS::from("b")),
+            Err(Kind::Repeat(ControlFlow::Recovable, Span::Range(0..0)))
+        );
+    }
+
+    #[test]
+    fn test_repeat() {
+        let mut input = TS::from("aaaa");
+        assert_eq!(a.repeat(1..3).parse(&mut input), Ok(vec![b'a'; 2]));
+        assert_eq!(input, TS::from((2, "aa")));
+
+        assert_eq!(
+            a.repeat(..=3).parse(&mut TS::from("aaaa")),
+            Ok(vec![b'a'; 3])
+        );
+
+        assert_eq!(
+            a.repeat(3..).parse(&mut TS::from("aab")),
+            Err(Kind::Repeat(ControlFlow::Recovable, Span::Range(0..2)))
+        );
+
+        assert_eq!(a.count(2).parse(&mut TS::from("aaa")), Ok(vec![b'a'; 2]));
+        assert_eq!(
+            a.count(2).parse(&mut TS::from("ab")),
+            Err(Kind::Repeat(ControlFlow::Recovable, Span::Range(0..1)))
+        );
+
+        assert_eq!(
+            a.fold_many(.., || 0, |acc, _| acc + 1)
+                .parse(&mut TS::from("aaaaa")),
+            Ok(5)
+        );
+    }
+
+    #[test]
+    fn test_repeat_fatal() {
+        let ab = |input: &mut TS<'_>| keyword("a").then(next(b'b').fatal()).value(()).parse(input);
+
+        assert_eq!(
+            ab.many0().parse(&mut TS::from("abac")),
+            Err(Kind::Next(ControlFlow::Fatal, Span::Range(3..4)))
+        );
+    }
+
+    #[test]
+    fn test_no_progress() {
+        let empty = |input: &mut TS<'_>| next(b'a').ok().value(()).parse(input);
+
+        assert_eq!(
+            empty.many0().parse(&mut TS::from("aab")),
+            Err(Kind::NoProgress(ControlFlow::Fatal, Span::Range(2..2)))
+        );
+
+        assert_eq!(
+            empty.separated_list0(empty).parse(&mut TS::from("b")),
+            Err(Kind::NoProgress(ControlFlow::Fatal, Span::Range(0..0)))
+        );
+
+        assert_eq!(empty.count(3).parse(&mut TS::from("b")), Ok(vec![(); 3]));
+
+        // a bounded repetition stops at the first empty success after its lower bound.
+        assert_eq!(
+            empty.repeat(2..=usize::MAX).parse(&mut TS::from("ab")),
+            Ok(vec![(); 2])
+        );
+        assert_eq!(
+            empty
+                .fold_many(0..1_000_000_000, || 0, |acc, _| acc + 1)
+                .parse(&mut TS::from("aab")),
+            Ok(2)
+        );
+    }
+
+    #[test]
+    fn test_separated_list() {
+        let mut input = TS::from("a,a,a,");
+        assert_eq!(
+            a.separated_list0(comma).parse(&mut input),
+            Ok(vec![b'a'; 3])
+        );
+        assert_eq!(input, TS::from((5, ",")));
+
+        assert_eq!(
+            a.separated_list0(comma).parse(&mut TS::from(",")),
+            Ok(vec![])
+        );
+
+        assert_eq!(
+            a.separated_list1(comma).parse(&mut TS::from(",")),
+            Err(Kind::SeparatedList(
+                ControlFlow::Recovable,
+                Span::Range(0..0)
+            ))
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "repeat: the range is empty, its lower bound 5 is greater than its upper bound 2"
+    )]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn test_repeat_inverted_range() {
+        _ = a.repeat(5..3);
+    }
+
+    #[test]
+    #[should_panic(expected = "repeat: the range is empty")]
+    fn test_repeat_empty_range() {
+        _ = a.repeat(..0);
+    }
+
+    #[test]
+    #[should_panic(expected = "repeat: the range is empty")]
+    fn test_repeat_excluded_max() {
+        _ = a.repeat((Bound::Excluded(usize::MAX), Bound::Unbounded));
+    }
+}
@@ -1,2 +1,6 @@
 /// Span type used by `parserc`.
 pub type Span = sourcespan::Span<usize>;
+
+pub use sourcespan::{
+    ColumnUnit, FileId, LineIndex, Location, Position, SourceFile, SourceMap, Spanned,
+};
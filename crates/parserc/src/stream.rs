@@ -0,0 +1,700 @@
+//! Streaming parsing over buffers that may end too early.
+
+use std::fmt::{Debug, Display};
+
+use crate::{
+    input::{AsBytes, AsStr, Find, Input, StartWith},
+    memo::MemoTable,
+    trivia::SkipTrivia,
+};
+
+/// An `Input` wrapper marks the end of the wrapped input as the end of the buffer,
+/// not the end of the stream.
+///
+/// Combinators reaching the end of a partial input return [`Incomplete`](crate::ControlFlow::Incomplete)
+/// errors, [`ok`](crate::Parser::ok) and [`fatal`](crate::Parser::fatal) pass them through,
+/// so the caller can refill the buffer and retry.
+pub struct Partial<I> {
+    input: I,
+    partial: bool,
+}
+
+impl<I> Partial<I> {
+    /// Wrap `input` as a partial input.
+    #[inline]
+    pub fn new(input: I) -> Self {
+        Self {
+            input,
+            partial: true,
+        }
+    }
+
+    /// Marks the end of this input as the end of the stream, combinators behave as on a complete input.
+    #[inline]
+    pub fn complete(mut self) -> Self {
+        self.partial = false;
+        self
+    }
+
+    /// Returns the wrapped input.
+    #[inline]
+    pub fn as_inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Consumes this wrapper and returns the wrapped input.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> From<I> for Partial<I> {
+    fn from(value: I) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<I> Clone for Partial<I>
+where
+    I: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            input: self.input.clone(),
+            partial: self.partial,
+        }
+    }
+}
+
+impl<I> Debug for Partial<I>
+where
+    I: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Partial")
+            .field("input", &self.input)
+            .field("partial", &self.partial)
+            .finish()
+    }
+}
+
+impl<I> PartialEq for Partial<I>
+where
+    I: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.input == other.input
+    }
+}
+
+impl<I> Input for Partial<I>
+where
+    I: Input,
+{
+    type Item = I::Item;
+
+    type Error = I::Error;
+
+    type Iter = I::Iter;
+
+    type IterIndices = I::IterIndices;
+
+    const HAS_TRIVIA: bool = I::HAS_TRIVIA;
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.input.len()
+    }
+
+    #[inline]
+    fn split_to(&mut self, at: usize) -> Self {
+        Self {
+            input: self.input.split_to(at),
+            partial: self.partial,
+        }
+    }
+
+    #[inline]
+    fn split_off(&mut self, at: usize) -> Self {
+        Self {
+            input: self.input.split_off(at),
+            partial: self.partial,
+        }
+    }
+
+    #[inline]
+    fn iter(&self) -> Self::Iter {
+        self.input.iter()
+    }
+
+    #[inline]
+    fn iter_indices(&self) -> Self::IterIndices {
+        self.input.iter_indices()
+    }
+
+    #[inline]
+    fn start(&self) -> usize {
+        self.input.start()
+    }
+
+    #[inline]
+    fn end(&self) -> usize {
+        self.input.end()
+    }
+
+    #[inline]
+    fn memo_table(&self) -> Option<&MemoTable> {
+        self.input.memo_table()
+    }
+
+    #[inline]
+    fn recover(&self, error: Self::Error) -> Result<(), Self::Error> {
+        self.input.recover(error)
+    }
+
+    #[inline]
+    fn report_expected(&self, name: impl Display) {
+        self.input.report_expected(name);
+    }
+
+    #[inline]
+    fn is_partial(&self) -> bool {
+        self.partial
+    }
+
+    #[inline]
+    fn skip_trivia(&mut self) -> Result<(), Self::Error> {
+        self.input.skip_trivia()
+    }
+
+    #[inline]
+    fn replace_trivia(&mut self, _skip: Option<SkipTrivia<Self>>) -> Option<SkipTrivia<Self>> {
+        crate::trivia::assert_outermost::<I>();
+        None
+    }
+}
+
+impl<I> AsBytes for Partial<I>
+where
+    I: AsBytes,
+{
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        self.input.as_bytes()
+    }
+}
+
+impl<I> AsStr for Partial<I>
+where
+    I: AsStr,
+{
+    #[inline]
+    fn as_str(&self) -> &str {
+        self.input.as_str()
+    }
+}
+
+impl<I, N> StartWith<N> for Partial<I>
+where
+    I: StartWith<N>,
+{
+    #[inline]
+    fn starts_with(&self, needle: N) -> Option<usize> {
+        self.input.starts_with(needle)
+    }
+
+    #[inline]
+    fn needed(&self, needle: N) -> Option<usize> {
+        self.input.needed(needle)
+    }
+}
+
+impl<I, N> Find<N> for Partial<I>
+where
+    I: Find<N>,
+{
+    #[inline]
+    fn find(&self, needle: N) -> Option<usize> {
+        self.input.find(needle)
+    }
+}
+
+#[cfg(feature = "lang")]
+impl<I> crate::lang::LangInput for Partial<I> where I: crate::lang::LangInput {}
+
+#[cfg(any(feature = "lang", feature = "binary"))]
+pub use reader::*;
+
+#[cfg(any(feature = "lang", feature = "binary"))]
+mod reader {
+    use std::io::{self, Read};
+
+    use crate::{ControlFlow, ParseError, input::Input};
+
+    use super::Partial;
+
+    /// Error returns by [`StreamReader`] parsing.
+    #[derive(thiserror::Error, Debug)]
+    pub enum StreamError<E> {
+        /// Failed to read from the underlying reader, or it returned invalid UTF-8.
+        #[error(transparent)]
+        Io(#[from] io::Error),
+        /// The parser failed, a complete stream was parsed if the error is `Incomplete`.
+        #[error("{0}")]
+        Parse(E),
+        /// The parser needs more bytes than the buffer limit, see [`StreamReader::max_buffer_size`].
+        #[error("stream buffer exceeds the limit of {0} bytes")]
+        BufferLimit(usize),
+    }
+
+    /// A driver that parses values from a [`Read`], refilling its buffer on
+    /// [`Incomplete`](ControlFlow::Incomplete) errors.
+    ///
+    /// Every [`parse`](StreamReader::parse) starts at a checkpoint, the end of the last parsed value,
+    /// and retries from it after each refill. Offsets are counted from the start of the stream.
+    pub struct StreamReader<R> {
+        reader: R,
+        /// unparsed bytes, starting at the checkpoint.
+        buf: Vec<u8>,
+        /// stream offset of `buf[0]`.
+        offset: usize,
+        /// the reader returned `0`.
+        eof: bool,
+        chunk_size: usize,
+        max_buffer_size: usize,
+    }
+
+    impl<R> StreamReader<R>
+    where
+        R: Read,
+    {
+        /// Create a driver that reads `8KiB` at a time.
+        #[inline]
+        pub fn new(reader: R) -> Self {
+            Self::with_chunk_size(reader, 8 * 1024)
+        }
+
+        /// Create a driver that reads at most `chunk_size` bytes at a time.
+        #[inline]
+        pub fn with_chunk_size(reader: R, chunk_size: usize) -> Self {
+            Self {
+                reader,
+                buf: vec![],
+                offset: 0,
+                eof: false,
+                chunk_size: chunk_size.max(1),
+                max_buffer_size: 64 * 1024 * 1024,
+            }
+        }
+
+        /// Set the maximum size of the unparsed buffer, `64MiB` by default.
+        ///
+        /// A parse that needs a larger buffer fails with [`StreamError::BufferLimit`].
+        #[inline]
+        pub fn max_buffer_size(mut self, max_buffer_size: usize) -> Self {
+            self.max_buffer_size = max_buffer_size;
+            self
+        }
+
+        /// Returns the stream offset of the checkpoint.
+        #[inline]
+        pub fn offset(&self) -> usize {
+            self.offset
+        }
+
+        /// Returns the buffered bytes not parsed yet.
+        #[inline]
+        pub fn buffer(&self) -> &[u8] {
+            &self.buf
+        }
+
+        /// Consumes this driver and returns the reader, buffered bytes are dropped.
+        #[inline]
+        pub fn into_inner(self) -> R {
+            self.reader
+        }
+
+        /// Returns true if the stream is exhausted and every byte is parsed.
+        pub fn is_finished(&mut self) -> io::Result<bool> {
+            if self.buf.is_empty() && !self.eof {
+                self.fill(1)?;
+            }
+
+            Ok(self.buf.is_empty() && self.eof)
+        }
+
+        /// Parse one value from the checkpoint by `f`, and move the checkpoint to its end.
+        ///
+        /// `f` receives a [`Partial`] input until the reader is exhausted, then a complete one.
+        /// The output can't borrow the buffer, map slices into owned values or [`Spanned`](crate::Spanned) ones.
+        #[cfg(feature = "lang")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "lang")))]
+        pub fn parse<E, O, F>(&mut self, mut f: F) -> Result<O, StreamError<E>>
+        where
+            E: ParseError,
+            F: FnMut(&mut Partial<crate::lang::TokenStream<'_, E>>) -> Result<O, E>,
+        {
+            loop {
+                let source = self.source()?;
+                let mut input = self.partial(crate::lang::TokenStream::from((self.offset, source)));
+
+                match f(&mut input) {
+                    Ok(output) => {
+                        self.advance(input.start());
+                        return Ok(output);
+                    }
+                    Err(err) => self.retry(err)?,
+                }
+            }
+        }
+
+        /// Like [`parse`](StreamReader::parse), but on raw bytes that may not be valid UTF-8.
+        #[cfg(feature = "binary")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "binary")))]
+        pub fn parse_bytes<E, O, F>(&mut self, mut f: F) -> Result<O, StreamError<E>>
+        where
+            E: ParseError,
+            F: FnMut(&mut Partial<crate::binary::ByteStream<'_, E>>) -> Result<O, E>,
+        {
+            loop {
+                let mut input = self.partial(crate::binary::ByteStream::from((
+                    self.offset,
+                    self.buf.as_slice(),
+                )));
+
+                match f(&mut input) {
+                    Ok(output) => {
+                        self.advance(input.start());
+                        return Ok(output);
+                    }
+                    Err(err) => self.retry(err)?,
+                }
+            }
+        }
+
+        /// Like [`parse_bytes`](StreamReader::parse_bytes), but reads bits in `order` by a [`BitInput`](crate::bits::BitInput).
+        ///
+        /// The checkpoint moves to the next byte boundary, the unread bits of the last byte are dropped.
+        #[cfg(feature = "binary")]
+        #[cfg_attr(docsrs, doc(cfg(feature = "binary")))]
+        pub fn parse_bits<E, O, F>(
+            &mut self,
+            order: crate::bits::BitOrder,
+            mut f: F,
+        ) -> Result<O, StreamError<E>>
+        where
+            E: ParseError,
+            F: FnMut(
+                &mut crate::bits::BitInput<Partial<crate::binary::ByteStream<'_, E>>>,
+            ) -> Result<O, E>,
+        {
+            use crate::bits::BitInput;
+
+            loop {
+                let mut input = BitInput::with_order(
+                    self.partial(crate::binary::ByteStream::from((
+                        self.offset,
+                        self.buf.as_slice(),
+                    ))),
+                    order,
+                );
+
+                match f(&mut input) {
+                    Ok(output) => {
+                        self.advance(input.start().div_ceil(8));
+                        return Ok(output);
+                    }
+                    Err(err) => {
+                        // the missing region is in bits, refill the bytes holding it.
+                        let needed = err.needed().and_then(|_| {
+                            BitInput::<Partial<crate::binary::ByteStream<'_, E>>>::byte_span(
+                                &err.to_span(),
+                            )
+                            .len()
+                        });
+
+                        self.retry_with(err, needed)?
+                    }
+                }
+            }
+        }
+
+        /// Wrap `input` of the buffer, it's complete once the reader is exhausted.
+        fn partial<I>(&self, input: I) -> Partial<I> {
+            if self.eof {
+                Partial::new(input).complete()
+            } else {
+                Partial::new(input)
+            }
+        }
+
+        /// Move the checkpoint to the stream offset `to`, and drop the parsed bytes.
+        fn advance(&mut self, to: usize) {
+            let consumed = to - self.offset;
+            self.buf.drain(..consumed);
+            self.offset = to;
+        }
+
+        /// Refill the buffer if `err` is incomplete and the stream isn't exhausted, otherwise returns it.
+        fn retry<E>(&mut self, err: E) -> Result<(), StreamError<E>>
+        where
+            E: ParseError,
+        {
+            let needed = err.needed();
+            self.retry_with(err, needed)
+        }
+
+        /// Like [`retry`](Self::retry), with the `needed` bytes of `err` counted by the caller.
+        fn retry_with<E>(&mut self, err: E, needed: Option<usize>) -> Result<(), StreamError<E>>
+        where
+            E: ParseError,
+        {
+            if err.control_flow() != ControlFlow::Incomplete || self.eof {
+                return Err(StreamError::Parse(err));
+            }
+
+            let needed = needed.unwrap_or(1);
+
+            if self.buf.len().saturating_add(needed) > self.max_buffer_size {
+                return Err(StreamError::BufferLimit(self.max_buffer_size));
+            }
+
+            Ok(self.fill(needed)?)
+        }
+
+        /// Returns the longest valid UTF-8 prefix of the buffer.
+        ///
+        /// A char split by the buffer end is left to the next refill.
+        #[cfg(feature = "lang")]
+        fn source(&self) -> io::Result<&str> {
+            match std::str::from_utf8(&self.buf) {
+                Ok(source) => Ok(source),
+                Err(err) if err.error_len().is_none() && !self.eof => {
+                    // the prefix is checked by `from_utf8`.
+                    Ok(std::str::from_utf8(&self.buf[..err.valid_up_to()]).unwrap())
+                }
+                Err(err) => Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+            }
+        }
+
+        /// Read at least `needed` more bytes, or until the reader is exhausted.
+        ///
+        /// The buffer grows by at most `chunk_size` per read, capped by `max_buffer_size`.
+        fn fill(&mut self, needed: usize) -> io::Result<()> {
+            let target = self.buf.len().saturating_add(needed);
+
+            while self.buf.len() < target && !self.eof {
+                let len = self.buf.len();
+                let chunk = self
+                    .chunk_size
+                    .min(self.max_buffer_size.saturating_sub(len));
+
+                if chunk == 0 {
+                    break;
+                }
+
+                self.buf.resize(len + chunk, 0);
+
+                match self.reader.read(&mut self.buf[len..]) {
+                    Ok(read) => {
+                        self.buf.truncate(len + read);
+                        self.eof = read == 0;
+                    }
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => {
+                        self.buf.truncate(len);
+                    }
+                    Err(err) => {
+                        self.buf.truncate(len);
+                        return Err(err);
+                    }
+                }
+            }
+
+            Ok(())
+        }
+    }
+}
+
+#[cfg(all(test, feature = "lang"))]
+mod tests {
+    use std::io::Read;
+
+    use crate::{
+        ControlFlow, Input, Kind, ParseError, Parser, Span, keyword, lang::TokenStream, next,
+        take_until, take_while,
+    };
+
+    use super::{Partial, StreamError, StreamReader};
+
+    type TS<'a> = TokenStream<'a, Kind>;
+
+    #[test]
+    fn test_incomplete() {
+        let mut input = Partial::new(TS::from("ke"));
+
+        let err = keyword("key").parse(&mut input.clone()).unwrap_err();
+        assert_eq!(
+            err,
+            Kind::Keyword(ControlFlow::Incomplete, Span::Range(2..3))
+        );
+        assert_eq!(err.needed(), Some(1));
+
+        // a mismatch is not incomplete.
+        assert_eq!(
+            keyword("ka").parse(&mut input.clone()),
+            Err(Kind::Keyword(ControlFlow::Recovable, Span::Range(0..2)))
+        );
+
+        assert_eq!(
+            take_while(|c: u8| c.is_ascii_alphabetic())
+                .ok()
+                .parse(&mut input.clone()),
+            Err(Kind::TakeWhile(
+                ControlFlow::Incomplete,
+                Span::RangeFrom(2..)
+            ))
+        );
+
+        // `ok` and `fatal` keep the incomplete error.
+        assert_eq!(
+            next(b'k')
+                .then(next(b'e'))
+                .then(next(b'y').fatal())
+                .ok()
+                .parse(&mut input)
+                .map_err(|err| err.needed()),
+            Err(Some(1))
+        );
+
+        let mut input = Partial::new(TS::from("ke")).complete();
+        assert!(keyword("key").ok().parse(&mut input).unwrap().is_none());
+        assert!(!input.is_partial());
+    }
+
+    /// A reader returns at most 3 bytes at a time.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl<'a> Read for Trickle<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = buf.len().min(self.0.len()).min(3);
+            buf[..len].copy_from_slice(&self.0[..len]);
+            self.0 = &self.0[len..];
+            Ok(len)
+        }
+    }
+
+    #[test]
+    fn test_stream_reader() {
+        let mut reader =
+            StreamReader::with_chunk_size(Trickle("GET /a\r\nGET /bé\r\nPUT".as_bytes()), 1);
+
+        let mut lines = vec![];
+
+        while !reader.is_finished().unwrap() {
+            let line = reader.parse(|input: &mut Partial<TS<'_>>| {
+                keyword("GET ")
+                    .then(take_until("\r\n"))
+                    .then(keyword("\r\n"))
+                    .map(|((_, path), _): ((_, Partial<TS<'_>>), _)| {
+                        (path.start(), path.as_inner().value.to_string())
+                    })
+                    .parse(input)
+            });
+
+            match line {
+                Ok(line) => lines.push(line),
+                Err(StreamError::Parse(err)) => {
+                    assert_eq!(
+                        err,
+                        Kind::Keyword(ControlFlow::Recovable, Span::Range(18..19))
+                    );
+                    break;
+                }
+                Err(err) => panic!("{}", err),
+            }
+        }
+
+        assert_eq!(lines, vec![(4, "/a".to_string()), (12, "/bé".to_string())]);
+        assert_eq!(reader.offset(), 18);
+        // `P` already mismatches, the rest is not read.
+        assert_eq!(reader.buffer(), b"P");
+    }
+
+    #[cfg(feature = "binary")]
+    #[test]
+    fn test_stream_reader_bytes() {
+        use crate::binary::{ByteStream, be_u16, length_prefixed};
+
+        let mut reader =
+            StreamReader::with_chunk_size(Trickle(b"\x00\x02\xff\xfe\x00\x04abcd\x00"), 1);
+
+        let mut frames = vec![];
+
+        while !reader.is_finished().unwrap() {
+            let frame = reader.parse_bytes(|input: &mut Partial<ByteStream<'_, Kind>>| {
+                length_prefixed(be_u16, 1024)
+                    .map(|frame: Partial<ByteStream<'_, Kind>>| frame.as_inner().value.to_vec())
+                    .parse(input)
+            });
+
+            match frame {
+                Ok(frame) => frames.push(frame),
+                Err(err) => {
+                    assert!(matches!(
+                        err,
+                        StreamError::Parse(Kind::Take(ControlFlow::Incomplete, _))
+                    ));
+                    break;
+                }
+            }
+        }
+
+        assert_eq!(frames, vec![vec![0xff, 0xfe], b"abcd".to_vec()]);
+        assert_eq!(reader.offset(), 10);
+    }
+
+    #[cfg(feature = "binary")]
+    #[test]
+    fn test_stream_reader_bits() {
+        use crate::{
+            binary::ByteStream,
+            bits::{BitInput, BitOrder, take_bits},
+        };
+
+        // 12-bit values, each one padded to a byte boundary.
+        let mut reader = StreamReader::with_chunk_size(Trickle(b"\xab\xc0\x12\x30"), 1);
+
+        let mut values = vec![];
+
+        while !reader.is_finished().unwrap() {
+            values.push(
+                reader
+                    .parse_bits(
+                        BitOrder::Msb,
+                        |input: &mut BitInput<Partial<ByteStream<'_, Kind>>>| {
+                            take_bits(12).parse(input)
+                        },
+                    )
+                    .unwrap(),
+            );
+        }
+
+        assert_eq!(values, vec![0xabc, 0x123]);
+        assert_eq!(reader.offset(), 4);
+    }
+
+    #[test]
+    fn test_stream_reader_limit() {
+        use crate::take;
+
+        let mut reader = StreamReader::with_chunk_size(Trickle(&[b'a'; 32]), 4).max_buffer_size(8);
+
+        let err = reader
+            .parse(|input: &mut Partial<TS<'_>>| take(16).map(|_: Partial<TS<'_>>| ()).parse(input))
+            .unwrap_err();
+
+        assert!(matches!(err, StreamError::BufferLimit(8)));
+        assert!(reader.buffer().len() <= 8);
+    }
+}
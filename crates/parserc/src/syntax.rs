@@ -2,8 +2,11 @@
 
 use std::{fmt::Debug, marker::PhantomData};
 
-use crate::{ControlFlow, Kind, Span, next};
-use crate::{input::Input, parser::Parser};
+use crate::{ControlFlow, Kind, Recovered, Span, Spanned, next};
+use crate::{
+    input::{Input, Item},
+    parser::Parser,
+};
 
 /// An extension trait to help syntax struct parsing.
 pub trait InputSyntaxExt: Input {
@@ -34,6 +37,38 @@ where
     fn into_parser() -> impl Parser<I, Output = Self> {
         SyntaxParser(Default::default(), Default::default())
     }
+
+    /// Returns an error node that covers the `span` skipped by error recovery.
+    ///
+    /// Returns `None` by default, which means this syntax can't be recovered.
+    #[inline]
+    fn error_node(_span: Span) -> Option<Self> {
+        None
+    }
+}
+
+/// Returns the span of a value not parsed by its own `Syntax`,
+/// e.g. a `#[from(Type)]` field of a derived syntax.
+pub trait ToSpan {
+    /// Returns the region of the source code this value is parsed from.
+    fn to_span(&self) -> Span;
+}
+
+impl<I> ToSpan for I
+where
+    I: Input,
+{
+    #[inline]
+    fn to_span(&self) -> Span {
+        Input::to_span(self)
+    }
+}
+
+impl<T> ToSpan for Spanned<T> {
+    #[inline]
+    fn to_span(&self) -> Span {
+        self.span.clone()
+    }
 }
 
 struct SyntaxParser<S, T>(PhantomData<S>, PhantomData<T>);
@@ -46,7 +81,7 @@ where
     type Output = T;
 
     #[inline]
-    fn parse(self, input: &mut I) -> Result<Self::Output, I::Error> {
+    fn parse(&mut self, input: &mut I) -> Result<Self::Output, I::Error> {
         T::parse(input)
     }
 }
@@ -57,7 +92,7 @@ where
 {
     #[inline]
     fn parse(_input: &mut I) -> Result<Self, I::Error> {
-        Ok(Self::default())
+        Ok(PhantomData)
     }
 
     #[inline]
@@ -95,6 +130,56 @@ where
     fn to_span(&self) -> Span {
         self.as_ref().to_span()
     }
+
+    #[inline]
+    fn error_node(span: Span) -> Option<Self> {
+        T::error_node(span).map(Box::new)
+    }
+}
+
+impl<T, I> Syntax<I> for Recovered<T>
+where
+    T: Syntax<I>,
+    I: Input,
+{
+    #[inline]
+    fn parse(input: &mut I) -> Result<Self, I::Error> {
+        T::parse(input).map(Self::Ok)
+    }
+
+    #[inline]
+    fn to_span(&self) -> Span {
+        match self {
+            Recovered::Ok(value) => value.to_span(),
+            Recovered::Error(span) => span.clone(),
+        }
+    }
+
+    #[inline]
+    fn error_node(span: Span) -> Option<Self> {
+        Some(Self::Error(span))
+    }
+}
+
+impl<T, I> Syntax<I> for Spanned<T>
+where
+    T: Syntax<I>,
+    I: Input,
+{
+    #[inline]
+    fn parse(input: &mut I) -> Result<Self, I::Error> {
+        T::into_parser().spanned().parse(input)
+    }
+
+    #[inline]
+    fn to_span(&self) -> Span {
+        self.span.clone()
+    }
+
+    #[inline]
+    fn error_node(span: Span) -> Option<Self> {
+        T::error_node(span.clone()).map(|value| Spanned::new(value, span))
+    }
 }
 
 impl<T, I> Syntax<I> for Vec<T>
@@ -104,13 +189,19 @@ where
 {
     fn parse(input: &mut I) -> Result<Self, I::Error> {
         let mut elms = vec![];
+        let mut parser = T::into_parser().ok();
+
         loop {
-            let elm = T::into_parser().ok().parse(input)?;
+            let start = input.start();
+
+            let elm = parser.parse(input)?;
 
             let Some(elm) = elm else {
                 break;
             };
 
+            input.ensure_progress(start)?;
+
             elms.push(elm);
         }
 
@@ -139,7 +230,10 @@ where
 {
     #[inline]
     fn parse(input: &mut I) -> Result<Self, <I as Input>::Error> {
-        next(C).map(|input| Self(input)).parse(input)
+        next(C)
+            .map(|input| Self(input))
+            .parse(input)
+            .inspect_err(|_| input.report_expected(format_args!("`{}`", C)))
     }
 
     #[inline]
@@ -161,7 +255,10 @@ where
 {
     #[inline]
     fn parse(input: &mut I) -> Result<Self, <I as Input>::Error> {
-        next(C).map(|input| Self(input)).parse(input)
+        next(C)
+            .map(|input| Self(input))
+            .parse(input)
+            .inspect_err(|_| input.report_expected(format_args!("`{}`", C as char)))
     }
 
     #[inline]
@@ -171,6 +268,9 @@ where
 }
 
 /// A short syntax for grouping token that surrounds a syntax body.
+///
+/// If the body fails and has an [`error_node`](Syntax::error_node), e.g. [`Recovered`],
+/// a [`Recovery`](crate::Recovery) input skips to the matching `End` token and continues.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Delimiter<Start, End, Body> {
@@ -191,7 +291,25 @@ where
 {
     fn parse(input: &mut I) -> Result<Self, I::Error> {
         let start = Start::parse(input)?;
-        let body = Body::into_parser().fatal().parse(input)?;
+
+        let snapshot = input.clone();
+
+        let body = match Body::into_parser().fatal().parse(input) {
+            Ok(body) => body,
+            // only a body with an error node can be recovered.
+            Err(err) if Body::error_node(Span::None).is_some() => {
+                *input = snapshot;
+                input.recover(err)?;
+
+                let from = input.start();
+
+                skip_to_end::<_, Start, End>(input)?;
+
+                Body::error_node(Span::Range(from..input.start())).expect("error node")
+            }
+            Err(err) => return Err(err),
+        };
+
         let end = End::into_parser().fatal().parse(input)?;
 
         Ok(Self { start, body, end })
@@ -206,6 +324,44 @@ where
     }
 }
 
+/// Skip items until the `End` token that closes the current nesting level, `End` is not consumed.
+fn skip_to_end<I, Start, End>(input: &mut I) -> Result<(), I::Error>
+where
+    I: Input + Clone,
+    Start: Syntax<I>,
+    End: Syntax<I>,
+{
+    let mut depth = 0usize;
+
+    loop {
+        let mut lookahead = input.clone();
+
+        if End::into_parser().ok().parse(&mut lookahead)?.is_some() {
+            if depth == 0 {
+                return Ok(());
+            }
+
+            depth -= 1;
+            *input = lookahead;
+            continue;
+        }
+
+        let mut lookahead = input.clone();
+
+        if Start::into_parser().ok().parse(&mut lookahead)?.is_some() {
+            depth += 1;
+            *input = lookahead;
+            continue;
+        }
+
+        let Some(item) = input.iter().next() else {
+            return Ok(());
+        };
+
+        input.split_to(item.len());
+    }
+}
+
 /// Limits the child `syntax` length.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -221,15 +377,9 @@ where
 
         let t = T::parse(input)?;
 
-        let span = t.to_span();
-
-        let len = match span {
-            sourcespan::Span::None => 0,
-            sourcespan::Span::Range(range) => range.len(),
-            sourcespan::Span::RangeTo(range_to) => range_to.end,
-            _ => {
-                return Err(Kind::LimitsTo(ControlFlow::Recovable, start).into());
-            }
+        // an unbounded span has no length.
+        let Some(len) = t.to_span().len() else {
+            return Err(Kind::LimitsTo(ControlFlow::Recovable, start).into());
         };
 
         if len > N {
@@ -259,18 +409,12 @@ where
 
         let t = T::parse(input)?;
 
-        let span = t.to_span();
-
-        let len = match span {
-            sourcespan::Span::None => 0,
-            sourcespan::Span::Range(range) => range.len(),
-            sourcespan::Span::RangeTo(range_to) => range_to.end,
-            _ => {
-                return Err(Kind::Limits(ControlFlow::Recovable, start).into());
-            }
+        // an unbounded span has no length.
+        let Some(len) = t.to_span().len() else {
+            return Err(Kind::Limits(ControlFlow::Recovable, start).into());
         };
 
-        if len < LOWER || !(len < HIGHER) {
+        if len < LOWER || len >= HIGHER {
             return Err(Kind::Limits(ControlFlow::Recovable, start).into());
         }
 
@@ -297,15 +441,9 @@ where
 
         let t = T::parse(input)?;
 
-        let span = t.to_span();
-
-        let len = match span {
-            sourcespan::Span::None => 0,
-            sourcespan::Span::Range(range) => range.len(),
-            sourcespan::Span::RangeTo(range_to) => range_to.end,
-            _ => {
-                return Err(Kind::Limits(ControlFlow::Recovable, start).into());
-            }
+        // an unbounded span has no length.
+        let Some(len) = t.to_span().len() else {
+            return Err(Kind::Limits(ControlFlow::Recovable, start).into());
         };
 
         if len < LOWER {
@@ -338,15 +476,19 @@ where
 {
     fn parse(input: &mut I) -> Result<Self, I::Error> {
         let mut pairs = vec![];
+        let mut t_parser = T::into_parser().ok();
+        let mut p_parser = P::into_parser().ok();
 
         loop {
-            let t = T::into_parser().ok().parse(input)?;
+            let start = input.start();
+
+            let t = t_parser.parse(input)?;
 
             let Some(t) = t else {
                 return Ok(Self { pairs, tail: None });
             };
 
-            let p = P::into_parser().ok().parse(input)?;
+            let p = p_parser.parse(input)?;
 
             let Some(p) = p else {
                 return Ok(Self {
@@ -355,6 +497,8 @@ where
                 });
             };
 
+            input.ensure_progress(start)?;
+
             pairs.push((t, p));
         }
     }
@@ -412,7 +556,12 @@ where
     fn parse_with_prefix(prefix: Self::P, input: &mut I) -> Result<Self, I::Error>;
 
     /// Create a new `Parser` with parsed prefix subtree.
-    fn into_parser_with_prefix(prefix: Self::P) -> impl Parser<I, Output = Self> {
+    ///
+    /// The prefix is cloned on every execution of the returned parser.
+    fn into_parser_with_prefix(prefix: Self::P) -> impl Parser<I, Output = Self>
+    where
+        Self::P: Clone,
+    {
         PartialSyntaxParser(prefix, Default::default(), Default::default())
     }
 }
@@ -423,11 +572,12 @@ impl<I, P, T> Parser<I> for PartialSyntaxParser<I, P, T>
 where
     I: Input,
     T: PartialSyntax<I, P = P>,
+    P: Clone,
 {
     type Output = T;
 
-    fn parse(self, input: &mut I) -> Result<Self::Output, I::Error> {
-        T::parse_with_prefix(self.0, input)
+    fn parse(&mut self, input: &mut I) -> Result<Self::Output, I::Error> {
+        T::parse_with_prefix(self.0.clone(), input)
     }
 }
 
@@ -438,11 +588,13 @@ pub use parserc_derive::Syntax;
 
 #[cfg(test)]
 mod tests {
+    use std::marker::PhantomData;
+
     use crate::{
-        Kind,
+        ControlFlow, Kind, Recovered, Recovery, Span, Spanned,
         input::Input,
         lang::TokenStream,
-        syntax::{Byte, Syntax},
+        syntax::{Byte, Delimiter, Punctuated, Syntax},
     };
 
     #[allow(unused)]
@@ -461,6 +613,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_no_progress() {
+        type H<I> = Byte<I, b'H'>;
+
+        assert_eq!(
+            Vec::<Option<H<_>>>::parse(&mut TokenStream::<'_, Kind>::from("HHx")),
+            Err(Kind::NoProgress(ControlFlow::Fatal, Span::Range(2..2)))
+        );
+
+        assert_eq!(
+            Punctuated::<PhantomData<()>, Option<H<_>>>::parse(&mut TokenStream::<'_, Kind>::from(
+                "x"
+            )),
+            Err(Kind::NoProgress(ControlFlow::Fatal, Span::Range(0..0)))
+        );
+    }
+
     #[test]
     fn test_byte() {
         type H<I> = Byte<I, b'H'>;
@@ -470,4 +639,57 @@ mod tests {
             Ok(Byte(TokenStream::from("H")))
         );
     }
+
+    #[test]
+    fn test_spanned() {
+        type H<I> = Byte<I, b'H'>;
+
+        assert_eq!(
+            Spanned::<Option<H<_>>>::parse(&mut TokenStream::<'_, Kind>::from((3, "x"))),
+            Ok(Spanned::new(None, Span::Range(3..3)))
+        );
+
+        assert_eq!(
+            Spanned::<H<_>>::parse(&mut TokenStream::<'_, Kind>::from((3, "H")))
+                .unwrap()
+                .map(|h| h.0.value),
+            Spanned::new("H", Span::Range(3..4))
+        );
+
+        assert_eq!(
+            Spanned::<Recovered<H<TokenStream<'_, Kind>>>>::error_node(Span::Range(1..2)),
+            Some(Spanned::new(
+                Recovered::Error(Span::Range(1..2)),
+                Span::Range(1..2)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_delimiter_recovery() {
+        type Paren<I> = Delimiter<Byte<I, b'('>, Byte<I, b')'>, Recovered<Byte<I, b'a'>>>;
+
+        let mut input = Recovery::new(TokenStream::<'_, Kind>::from("(a)(x(y))(a)"));
+        let parens = Vec::<Paren<_>>::parse(&mut input).unwrap();
+
+        assert!(input.is_empty());
+        assert_eq!(
+            parens
+                .iter()
+                .map(|paren| paren.body.clone().ok().is_some())
+                .collect::<Vec<_>>(),
+            vec![true, false, true]
+        );
+        assert_eq!(parens[1].body, Recovered::Error(Span::Range(4..8)));
+        assert_eq!(parens[1].to_span(), Span::Range(3..9));
+        assert_eq!(
+            input.take_errors(),
+            vec![Kind::Next(ControlFlow::Fatal, Span::Range(4..5))]
+        );
+
+        assert_eq!(
+            Vec::<Paren<_>>::parse(&mut TokenStream::<'_, Kind>::from("(a)(x(y))(a)")),
+            Err(Kind::Next(ControlFlow::Fatal, Span::Range(4..5)))
+        );
+    }
 }
@@ -0,0 +1,312 @@
+//! Skipping trivia (whitespace, comments ...) between the tokens of derived syntaxes.
+
+use std::{
+    any::TypeId,
+    fmt::{Debug, Display},
+};
+
+use crate::{
+    input::{AsBytes, AsStr, Find, Input, StartWith},
+    memo::MemoTable,
+};
+
+/// A function skips the trivia at the start of input `I`, tagged with the type of the trivia syntax.
+///
+/// The tag identifies the skipper in [`MemoKey`](crate::MemoKey)s, function addresses are not unique.
+pub struct SkipTrivia<I>
+where
+    I: Input + ?Sized,
+{
+    skip: fn(&mut I) -> Result<(), I::Error>,
+    tag: TypeId,
+}
+
+impl<I> SkipTrivia<I>
+where
+    I: Input + ?Sized,
+{
+    /// Create a skipper of the trivia syntax `T`.
+    #[inline]
+    pub fn new<T>(skip: fn(&mut I) -> Result<(), I::Error>) -> Self
+    where
+        T: ?Sized,
+    {
+        Self {
+            skip,
+            // trivia syntaxes borrow the input, `typeid` ignores their lifetimes.
+            tag: typeid::of::<T>(),
+        }
+    }
+
+    /// Returns the type of the trivia syntax.
+    #[inline]
+    pub fn tag(&self) -> TypeId {
+        self.tag
+    }
+
+    /// Skip the trivia at the start of `input`.
+    #[inline]
+    pub fn skip(&self, input: &mut I) -> Result<(), I::Error> {
+        (self.skip)(input)
+    }
+}
+
+impl<I> Clone for SkipTrivia<I>
+where
+    I: Input + ?Sized,
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<I> Copy for SkipTrivia<I> where I: Input + ?Sized {}
+
+impl<I> Debug for SkipTrivia<I>
+where
+    I: Input + ?Sized,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SkipTrivia").field(&self.tag).finish()
+    }
+}
+
+/// Fails to compile if `I` has a [`Trivia`] inside, called by the wrappers that can't forward a skipper.
+#[inline]
+pub(crate) fn assert_outermost<I>()
+where
+    I: Input,
+{
+    const {
+        assert!(
+            !I::HAS_TRIVIA,
+            "`Trivia` must be the outermost input wrapper, e.g. `Trivia<Memo<I>>`"
+        )
+    }
+}
+
+/// An `Input` wrapper holds the trivia skipper installed by `#[syntax(trivia = ...)]` syntaxes.
+///
+/// Derived syntaxes skip trivia before every field and around tokens, a syntax with `#[syntax(trivia = T)]`
+/// installs `T` for the syntaxes parsed inside it, and `#[syntax(no_trivia)]` keeps them adjacent.
+///
+/// The skipper is typed on the outermost input, so `Trivia` must wrap the other wrappers, e.g. `Trivia<Memo<I>>`.
+/// Derived syntaxes installing a skipper fail to compile on the other order, e.g. `Memo<Trivia<I>>`.
+pub struct Trivia<I>
+where
+    I: Input,
+{
+    input: I,
+    skip: Option<SkipTrivia<Self>>,
+}
+
+impl<I> Trivia<I>
+where
+    I: Input,
+{
+    /// Wrap `input` without a trivia skipper.
+    #[inline]
+    pub fn new(input: I) -> Self {
+        Self { input, skip: None }
+    }
+
+    /// Returns the wrapped input.
+    #[inline]
+    pub fn as_inner(&self) -> &I {
+        &self.input
+    }
+
+    /// Consumes this wrapper and returns the wrapped input.
+    #[inline]
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+}
+
+impl<I> From<I> for Trivia<I>
+where
+    I: Input,
+{
+    fn from(value: I) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<I> Clone for Trivia<I>
+where
+    I: Input + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            input: self.input.clone(),
+            skip: self.skip,
+        }
+    }
+}
+
+impl<I> Debug for Trivia<I>
+where
+    I: Input + Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Trivia")
+            .field("input", &self.input)
+            .field("skip", &self.skip.is_some())
+            .finish()
+    }
+}
+
+impl<I> PartialEq for Trivia<I>
+where
+    I: Input + PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.input == other.input
+    }
+}
+
+impl<I> Input for Trivia<I>
+where
+    I: Input,
+{
+    type Item = I::Item;
+
+    type Error = I::Error;
+
+    type Iter = I::Iter;
+
+    type IterIndices = I::IterIndices;
+
+    const HAS_TRIVIA: bool = true;
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.input.len()
+    }
+
+    #[inline]
+    fn split_to(&mut self, at: usize) -> Self {
+        Self {
+            input: self.input.split_to(at),
+            skip: self.skip,
+        }
+    }
+
+    #[inline]
+    fn split_off(&mut self, at: usize) -> Self {
+        Self {
+            input: self.input.split_off(at),
+            skip: self.skip,
+        }
+    }
+
+    #[inline]
+    fn iter(&self) -> Self::Iter {
+        self.input.iter()
+    }
+
+    #[inline]
+    fn iter_indices(&self) -> Self::IterIndices {
+        self.input.iter_indices()
+    }
+
+    #[inline]
+    fn start(&self) -> usize {
+        self.input.start()
+    }
+
+    #[inline]
+    fn end(&self) -> usize {
+        self.input.end()
+    }
+
+    #[inline]
+    fn memo_table(&self) -> Option<&MemoTable> {
+        self.input.memo_table()
+    }
+
+    #[inline]
+    fn recover(&self, error: Self::Error) -> Result<(), Self::Error> {
+        self.input.recover(error)
+    }
+
+    #[inline]
+    fn report_expected(&self, name: impl Display) {
+        self.input.report_expected(name);
+    }
+
+    #[inline]
+    fn is_partial(&self) -> bool {
+        self.input.is_partial()
+    }
+
+    fn skip_trivia(&mut self) -> Result<(), Self::Error> {
+        // the trivia syntax itself is parsed without skipping.
+        let Some(skip) = self.skip.take() else {
+            return Ok(());
+        };
+
+        let result = skip.skip(self);
+        self.skip = Some(skip);
+
+        result
+    }
+
+    #[inline]
+    fn replace_trivia(&mut self, skip: Option<SkipTrivia<Self>>) -> Option<SkipTrivia<Self>> {
+        std::mem::replace(&mut self.skip, skip)
+    }
+
+    #[inline]
+    fn trivia(&self) -> Option<SkipTrivia<Self>> {
+        self.skip
+    }
+}
+
+impl<I> AsBytes for Trivia<I>
+where
+    I: Input + AsBytes,
+{
+    #[inline]
+    fn as_bytes(&self) -> &[u8] {
+        self.input.as_bytes()
+    }
+}
+
+impl<I> AsStr for Trivia<I>
+where
+    I: Input + AsStr,
+{
+    #[inline]
+    fn as_str(&self) -> &str {
+        self.input.as_str()
+    }
+}
+
+impl<I, N> StartWith<N> for Trivia<I>
+where
+    I: Input + StartWith<N>,
+{
+    #[inline]
+    fn starts_with(&self, needle: N) -> Option<usize> {
+        self.input.starts_with(needle)
+    }
+
+    #[inline]
+    fn needed(&self, needle: N) -> Option<usize> {
+        self.input.needed(needle)
+    }
+}
+
+impl<I, N> Find<N> for Trivia<I>
+where
+    I: Input + Find<N>,
+{
+    #[inline]
+    fn find(&self, needle: N) -> Option<usize> {
+        self.input.find(needle)
+    }
+}
+
+#[cfg(feature = "lang")]
+impl<I> crate::lang::LangInput for Trivia<I> where I: crate::lang::LangInput {}
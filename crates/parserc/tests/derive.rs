@@ -1 +1,867 @@
+use std::cell::Cell;
 
+use parserc::{
+    AsStr, ControlFlow, Furthest, Input, Kind, Memo, MemoKey, ParseError, Parser, Partial,
+    Recovered, Recovery, SkipTrivia, SourceMap, Span, Spanned, Trivia,
+    lang::{LangInput, TokenStream},
+    next_if,
+    pratt::{self, Assoc, Operator},
+    syntax::{Delimiter, InputSyntaxExt, Syntax, ToSpan},
+    take_while_range_from,
+};
+
+type TS<'a> = TokenStream<'a, Kind>;
+
+thread_local! {
+    static DIGITS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// token `(`
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(char = b'(')]
+struct ParenStart<I>(I)
+where
+    I: LangInput;
+
+/// token `)`
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(char = b')')]
+struct ParenEnd<I>(I)
+where
+    I: LangInput;
+
+/// token `*`
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(char = b'*')]
+struct Star<I>(I)
+where
+    I: LangInput;
+
+/// token `+`
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(char = b'+')]
+struct Plus<I>(I)
+where
+    I: LangInput;
+
+/// A digit that counts how many times it is parsed.
+#[derive(Debug, PartialEq, Clone)]
+struct Digit<I>(I);
+
+impl<I> Syntax<I> for Digit<I>
+where
+    I: LangInput,
+{
+    fn parse(input: &mut I) -> Result<Self, I::Error> {
+        DIGITS.with(|digits| digits.set(digits.get() + 1));
+        next_if(|c: u8| c.is_ascii_digit())
+            .map(Self)
+            .label("digit")
+            .parse(input)
+    }
+
+    fn to_span(&self) -> Span {
+        self.0.to_span()
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(memo)]
+enum Expr<I>
+where
+    I: LangInput,
+{
+    Mul(Primary<I>, Star<I>, Box<Expr<I>>),
+    Primary(Primary<I>),
+}
+
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(memo)]
+enum Primary<I>
+where
+    I: LangInput,
+{
+    Paren(Delimiter<ParenStart<I>, ParenEnd<I>, Box<Expr<I>>>),
+    Digit(Digit<I>),
+}
+
+fn count_digits<I>(mut input: I) -> (Span, usize)
+where
+    I: LangInput<Error = Kind>,
+{
+    DIGITS.with(|digits| digits.set(0));
+    let expr: Expr<I> = input.parse().unwrap();
+    assert!(input.is_empty());
+    (expr.to_span(), DIGITS.with(|digits| digits.get()))
+}
+
+#[test]
+fn test_memo() {
+    let source = "((((((((((1))))))))))*2";
+
+    let (span, plain) = count_digits(TS::from(source));
+    let (memo_span, memo) = count_digits(Memo::new(TS::from(source)));
+
+    assert_eq!(span, Span::Range(0..source.len()));
+    assert_eq!(memo_span, span);
+    assert_eq!(plain, 1026);
+    assert_eq!(memo, 13);
+}
+
+/// An `Expr` with spaces between its tokens.
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(trivia = Ws<I>)]
+struct SpacedExpr<I>(Expr<I>)
+where
+    I: LangInput;
+
+#[test]
+fn test_memo_table() {
+    let mut input = Memo::new(TS::from("*"));
+
+    assert!(input.parse::<Expr<_>>().is_err());
+    assert_eq!(
+        input
+            .table()
+            .get(&MemoKey::new::<Expr<Memo<TS<'_>>>, _>(&input)),
+        Some(None)
+    );
+    assert_eq!(
+        input
+            .table()
+            .get(&MemoKey::new::<Primary<Memo<TS<'_>>>, _>(&input)),
+        Some(None)
+    );
+    assert_eq!(input.table().len(), 2);
+
+    // results under another trivia skipper are recorded apart.
+    let mut input = Trivia::new(Memo::new(TS::from("1 * 2")));
+
+    assert!(matches!(
+        input.clone().parse::<Expr<_>>(),
+        Ok(Expr::Primary(_))
+    ));
+    assert!(matches!(
+        input.parse::<SpacedExpr<_>>(),
+        Ok(SpacedExpr(Expr::Mul(..)))
+    ));
+    assert!(input.is_empty());
+
+    // skippers are told apart by their trivia syntax type, not their address.
+    let mut ws = Trivia::new(Memo::new(TS::from("1")));
+    let mut other_ws = ws.clone();
+    let mut dot = ws.clone();
+
+    ws.replace_trivia(Some(SkipTrivia::new::<Ws<TS<'_>>>(|_| Ok(()))));
+    other_ws.replace_trivia(Some(SkipTrivia::new::<Ws<TS<'_>>>(|input| {
+        input.split_to(0);
+        Ok(())
+    })));
+    dot.replace_trivia(Some(SkipTrivia::new::<Dot<TS<'_>>>(|_| Ok(()))));
+
+    let key = MemoKey::new::<Expr<Memo<TS<'_>>>, _>(&ws);
+    assert_eq!(key, MemoKey::new::<Expr<Memo<TS<'_>>>, _>(&other_ws));
+    assert_ne!(key, MemoKey::new::<Expr<Memo<TS<'_>>>, _>(&dot));
+}
+
+#[test]
+fn test_expected() {
+    for (source, message) in [
+        ("(1;", "expected `*` or `)`, found `;`"),
+        ("(", "expected `(` or digit, found end of input"),
+    ] {
+        let mut input = Furthest::new(TS::from(source));
+
+        assert!(input.parse::<Expr<_>>().is_err());
+        assert_eq!(input.expected().message(source), message);
+    }
+}
+
+/// token `{`
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(char = b'{')]
+struct BraceStart<I>(I)
+where
+    I: LangInput;
+
+/// token `}`
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(char = b'}')]
+struct BraceEnd<I>(I)
+where
+    I: LangInput;
+
+/// keyword `let`
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(keyword = "let")]
+struct KwLet<I>(I)
+where
+    I: LangInput;
+
+/// token `=`
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(char = b'=')]
+struct Assign<I>(I)
+where
+    I: LangInput;
+
+/// token `\n`
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(char = b'\n')]
+struct Newline<I>(I)
+where
+    I: LangInput;
+
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(context = "let statement")]
+struct Let<I>
+where
+    I: LangInput,
+{
+    kw: KwLet<I>,
+    assign: Assign<I>,
+    value: Digit<I>,
+}
+
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(context = "block")]
+struct Block<I>(Delimiter<BraceStart<I>, BraceEnd<I>, Let<I>>)
+where
+    I: LangInput;
+
+#[test]
+fn test_context() {
+    let err = TS::from("{let1}").parse::<Block<_>>().unwrap_err();
+
+    assert_eq!(err.contexts(), vec!["block", "let statement"]);
+    assert_eq!(
+        err.root(),
+        &Kind::Next(ControlFlow::Fatal, Span::Range(4..5))
+    );
+    assert_eq!(
+        err.to_string(),
+        "in block → in let statement → Error from `next` combinator"
+    );
+
+    assert!(TS::from("{let=1}").parse::<Block<_>>().is_ok());
+}
+
+#[test]
+fn test_source_map() {
+    let mut map = SourceMap::new();
+
+    let main = map.add("main.x", "{let=1}");
+    let lib = map.add("lib.x", "{let=2}\n{let1}");
+
+    let blocks = map
+        .files()
+        .iter()
+        .map(|file| TS::from(file.as_input()).parse::<Vec<Block<_>>>())
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        map.resolve(&blocks[0].as_ref().unwrap()[0].to_span()),
+        Some((main, Span::Range(0..7)))
+    );
+
+    let err = TS::from(map.get(lib).unwrap().as_input())
+        .parse::<(Block<_>, Newline<_>, Block<_>)>()
+        .unwrap_err();
+
+    assert_eq!(err.to_span(), Span::Range(20..21));
+    assert_eq!(
+        map.resolve(&err.to_span()),
+        Some((lib, Span::Range(12..13)))
+    );
+    assert_eq!(map.lookup(20).unwrap().to_string(), "lib.x:12");
+}
+
+#[derive(Debug, PartialEq, Clone, Syntax)]
+enum Sum<I>
+where
+    I: LangInput,
+{
+    Add(Box<Sum<I>>, Plus<I>, Digit<I>),
+    // field names don't clash with the locals of the generated code.
+    Mul {
+        lhs: Box<Self>,
+        star: Star<I>,
+        start: Digit<I>,
+    },
+    Digit(Digit<I>),
+}
+
+#[test]
+fn test_left_recursion() {
+    // left-recursive variants grow left-associatively without precedence,
+    // operator precedence needs `pratt::Expr`, see `test_precedence`.
+    let mut input = TS::from("1*2+3;");
+
+    assert_eq!(
+        input.parse(),
+        Ok(Sum::Add(
+            Box::new(Sum::Mul {
+                lhs: Box::new(Sum::Digit(Digit(TS::from("1")))),
+                star: Star(TS::from((1, "*"))),
+                start: Digit(TS::from((2, "2")))
+            }),
+            Plus(TS::from((3, "+"))),
+            Digit(TS::from((4, "3")))
+        ))
+    );
+    assert_eq!(input, TS::from((5, ";")));
+
+    assert_eq!(
+        TS::from("+").parse::<Sum<_>>(),
+        Err(Kind::Syntax(
+            "Sum",
+            ControlFlow::Recovable,
+            Span::Range(0..1)
+        ))
+    );
+}
+
+/// token `-`
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(char = b'-')]
+struct Minus<I>(I)
+where
+    I: LangInput;
+
+/// token `^`
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(char = b'^')]
+struct Caret<I>(I)
+where
+    I: LangInput;
+
+/// token `!`
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(char = b'!')]
+struct Bang<I>(I)
+where
+    I: LangInput;
+
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(precedence)]
+enum BinOp<I>
+where
+    I: LangInput,
+{
+    #[syntax(infix = 1)]
+    Add(Plus<I>),
+    #[syntax(infix = 2)]
+    Mul(Star<I>),
+    #[syntax(infix = 3, assoc = right)]
+    Pow(Caret<I>),
+}
+
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(precedence)]
+enum UnOp<I>
+where
+    I: LangInput,
+{
+    #[syntax(prefix = 2)]
+    Neg(Minus<I>),
+}
+
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(precedence)]
+enum PostOp<I>
+where
+    I: LangInput,
+{
+    #[syntax(postfix = 4)]
+    Fact(Bang<I>),
+}
+
+type Arith<I> = pratt::Expr<Digit<I>, BinOp<I>, UnOp<I>, PostOp<I>>;
+
+/// Render an expression tree with full parentheses.
+fn arith(expr: &Arith<TS<'_>>) -> String {
+    match expr {
+        pratt::Expr::Atom(digit) => digit.0.as_str().to_string(),
+        pratt::Expr::Prefix(_, rhs) => format!("(-{})", arith(rhs)),
+        pratt::Expr::Infix(lhs, op, rhs) => {
+            let op = match op {
+                BinOp::Add(_) => '+',
+                BinOp::Mul(_) => '*',
+                BinOp::Pow(_) => '^',
+            };
+
+            format!("({}{}{})", arith(lhs), op, arith(rhs))
+        }
+        pratt::Expr::Postfix(lhs, _) => format!("({}!)", arith(lhs)),
+    }
+}
+
+#[test]
+fn test_precedence() {
+    assert_eq!(
+        BinOp::Pow(Caret(TS::from("^"))).infix(),
+        Some((3, Assoc::Right))
+    );
+    assert_eq!(BinOp::Add(Plus(TS::from("+"))).prefix(), None);
+
+    for (source, expect) in [
+        ("1+2+3", "((1+2)+3)"),
+        ("1+2*3", "(1+(2*3))"),
+        ("2^3^4", "(2^(3^4))"),
+        ("-2*3", "((-2)*3)"),
+        ("-2^3", "(-(2^3))"),
+        ("-1+2*3^4^5!", "((-1)+(2*(3^(4^(5!)))))"),
+    ] {
+        let mut input = TS::from(source);
+        let expr: Arith<_> = input.parse().unwrap();
+
+        assert!(input.is_empty());
+        assert_eq!(arith(&expr), expect);
+        assert_eq!(expr.to_span(), Span::Range(0..source.len()));
+    }
+
+    let mut input = TS::from("1+;");
+    assert_eq!(
+        input.parse::<Arith<_>>(),
+        Err(Kind::NextIf(ControlFlow::Recovable, Span::Range(2..3)))
+    );
+}
+
+/// A run of ascii digits.
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(token = |c: u8| c.is_ascii_digit())]
+struct Digits<I>(I)
+where
+    I: LangInput;
+
+/// A number converted from its digits, keeps their span.
+#[derive(Debug, PartialEq, Clone)]
+struct Number(u64, Span);
+
+impl<I> From<Digits<I>> for Number
+where
+    I: LangInput,
+{
+    fn from(digits: Digits<I>) -> Self {
+        Self(digits.0.as_str().parse().unwrap(), digits.to_span())
+    }
+}
+
+impl ToSpan for Number {
+    fn to_span(&self) -> Span {
+        self.1.clone()
+    }
+}
+
+/// `let=digits`, commits after the keyword, the value must fit in a byte.
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[try_filter(|assignment| assignment.value.0 <= 255)]
+struct Assignment<I>
+where
+    I: LangInput,
+{
+    #[fatal]
+    kw: KwLet<I>,
+    assign: Assign<I>,
+    #[from(Digits<I>)]
+    value: Number,
+}
+
+#[derive(Debug, PartialEq, Clone, Syntax)]
+enum Stmt<I>
+where
+    I: LangInput,
+{
+    Assignment(Assignment<I>),
+    Number(#[from(Digits<I>)] Number),
+}
+
+#[test]
+fn test_field_attributes() {
+    let assignment = TS::from("let=42").parse::<Assignment<_>>().unwrap();
+
+    assert_eq!(assignment.value, Number(42, Span::Range(4..6)));
+    assert_eq!(assignment.to_span(), Span::Range(0..6));
+
+    // the `#[fatal]` field itself stays recoverable, the cut applies to the fields after it.
+    assert_eq!(
+        TS::from("x").parse::<Assignment<_>>(),
+        Err(Kind::Keyword(ControlFlow::Recovable, Span::Range(0..1)))
+    );
+    assert_eq!(
+        TS::from("let1").parse::<Assignment<_>>(),
+        Err(Kind::Next(ControlFlow::Fatal, Span::Range(3..4)))
+    );
+    assert_eq!(
+        TS::from("let=").parse::<Assignment<_>>(),
+        Err(Kind::TakeWhileRange(ControlFlow::Fatal, Span::Range(4..4)))
+    );
+    assert_eq!(
+        TS::from("let=256").parse::<Assignment<_>>(),
+        Err(Kind::Syntax(
+            "Assignment",
+            ControlFlow::Recovable,
+            Span::Range(0..7)
+        ))
+    );
+
+    let stmt = TS::from("12").parse::<Stmt<_>>().unwrap();
+
+    assert_eq!(stmt, Stmt::Number(Number(12, Span::Range(0..2))));
+    assert_eq!(stmt.to_span(), Span::Range(0..2));
+    assert!(matches!(
+        TS::from("let=255").parse::<Stmt<_>>(),
+        Ok(Stmt::Assignment(_))
+    ));
+    assert_eq!(
+        TS::from("let=256").parse::<Stmt<_>>(),
+        Err(Kind::Syntax(
+            "Stmt",
+            ControlFlow::Recovable,
+            Span::Range(0..7)
+        ))
+    );
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Syntax)]
+enum Op {
+    #[syntax(keyword = "+=")]
+    AddAssign,
+    #[syntax(keyword = "+")]
+    Add,
+    #[syntax(keyword = "-")]
+    Sub,
+    #[syntax(keyword = "->")]
+    Arrow,
+    #[syntax(keyword = "-=")]
+    SubAssign,
+}
+
+#[derive(Debug, PartialEq, Clone, Syntax)]
+enum Type<I>
+where
+    I: LangInput,
+{
+    #[syntax(keyword = "in")]
+    In(I),
+    #[syntax(keyword = "int")]
+    Int(I),
+    #[syntax(keyword = "i8")]
+    I8(I),
+}
+
+#[test]
+fn test_keyword_enum() {
+    for (source, op, rest) in [
+        ("+=1", Op::AddAssign, "1"),
+        ("+1", Op::Add, "1"),
+        ("-", Op::Sub, ""),
+        ("->", Op::Arrow, ""),
+        ("-=-", Op::SubAssign, "-"),
+    ] {
+        let mut input = TS::from(source);
+        assert_eq!(input.parse(), Ok(op));
+        assert_eq!(input.as_str(), rest);
+    }
+
+    assert_eq!(
+        TS::from("*").parse::<Op>(),
+        Err(Kind::Syntax(
+            "Op",
+            ControlFlow::Recovable,
+            Span::Range(0..1)
+        ))
+    );
+
+    let mut input = Furthest::new(TS::from("*"));
+    assert!(input.parse::<Op>().is_err());
+    assert_eq!(
+        input.expected().message("*"),
+        "expected `+=`, `+`, `-`, `->` or `-=`, found `*`"
+    );
+
+    // a unit variant doesn't keep the matched input.
+    assert_eq!(Syntax::<TS<'_>>::to_span(&Op::Add), Span::None);
+
+    let ty = TS::from("int").parse::<Type<_>>().unwrap();
+    assert_eq!(ty, Type::Int(TS::from("int")));
+    assert_eq!(ty.to_span(), Span::Range(0..3));
+
+    let mut input = TS::from("in t");
+    assert_eq!(
+        input.parse::<Type<_>>().unwrap().to_span(),
+        Span::Range(0..2)
+    );
+    assert_eq!(input.as_str(), " t");
+
+    // a longer keyword may follow.
+    assert_eq!(
+        Partial::new(TS::from("+")).parse::<Op>(),
+        Err(Kind::Syntax(
+            "Op",
+            ControlFlow::Incomplete,
+            Span::Range(1..2)
+        ))
+    );
+    assert_eq!(Partial::new(TS::from("+1")).parse::<Op>(), Ok(Op::Add));
+}
+
+#[derive(Debug, PartialEq, Clone, Syntax)]
+enum PeekSum<I>
+where
+    I: LangInput,
+{
+    #[syntax(peek = b'+')]
+    Add(Box<PeekSum<I>>, Plus<I>, PeekAtom<I>),
+    #[syntax(peek = b'(' | b'0'..=b'9')]
+    Atom(PeekAtom<I>),
+}
+
+#[derive(Debug, PartialEq, Clone, Syntax)]
+enum PeekAtom<I>
+where
+    I: LangInput,
+{
+    #[syntax(peek = b'(')]
+    Paren(Delimiter<ParenStart<I>, ParenEnd<I>, Box<PeekSum<I>>>),
+    #[syntax(peek = b'0'..=b'9')]
+    Digit(Digit<I>),
+}
+
+#[test]
+fn test_peek() {
+    DIGITS.with(|digits| digits.set(0));
+
+    let mut input = TS::from("(1+(2))+3");
+    assert_eq!(
+        input.parse::<PeekSum<_>>().unwrap().to_span(),
+        Span::Range(0..9)
+    );
+    assert!(input.is_empty());
+
+    // only variants that can start at the next item are tried.
+    assert_eq!(DIGITS.with(|digits| digits.get()), 3);
+    assert_eq!(
+        TS::from("x").parse::<PeekAtom<_>>(),
+        Err(Kind::Syntax(
+            "PeekAtom",
+            ControlFlow::Recovable,
+            Span::Range(0..1)
+        ))
+    );
+    assert_eq!(DIGITS.with(|digits| digits.get()), 3);
+
+    assert_eq!(
+        Partial::new(TS::from("")).parse::<PeekAtom<_>>(),
+        Err(Kind::Next(ControlFlow::Incomplete, Span::Range(0..1)))
+    );
+}
+
+fn number<I>(input: &mut I) -> Result<u64, I::Error>
+where
+    I: LangInput,
+{
+    take_while_range_from(1, |c: u8| c.is_ascii_digit())
+        .map(|digits: I| digits.as_str().parse().unwrap())
+        .parse(input)
+}
+
+/// `name=number` with computed fields.
+#[derive(Debug, PartialEq, Clone, Syntax)]
+struct Setting<I>
+where
+    I: LangInput,
+{
+    #[syntax(parser = take_while_range_from(1, |c: u8| c.is_ascii_alphabetic()))]
+    name: I,
+    #[fatal]
+    assign: Assign<I>,
+    #[syntax(parser = number.spanned())]
+    value: Spanned<u64>,
+    #[syntax(with = *value * 2)]
+    double: u64,
+    #[syntax(with = name.as_str().len())]
+    name_len: usize,
+    #[syntax(default)]
+    comments: Vec<String>,
+}
+
+#[test]
+fn test_field_parsers() {
+    let setting = TS::from("ab=21").parse::<Setting<_>>().unwrap();
+
+    assert_eq!(
+        setting,
+        Setting {
+            name: TS::from("ab"),
+            assign: Assign(TS::from((2, "="))),
+            value: Spanned::new(21, Span::Range(3..5)),
+            double: 42,
+            name_len: 2,
+            comments: vec![],
+        }
+    );
+
+    assert_eq!(setting.to_span(), Span::Range(0..5));
+
+    assert_eq!(
+        TS::from("ab=x").parse::<Setting<_>>(),
+        Err(Kind::TakeWhileRange(ControlFlow::Fatal, Span::Range(3..3)))
+    );
+}
+
+/// Field names don't clash with the locals of the generated code.
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(context = "hygiene")]
+#[try_filter(|_| true)]
+struct Hygiene<I>
+where
+    I: LangInput,
+{
+    input: Digits<I>,
+    parser: Assign<I>,
+    #[syntax(parser = number.spanned())]
+    value: Spanned<u64>,
+    #[syntax(with = *value + 1)]
+    start: u64,
+}
+
+#[derive(Debug, PartialEq, Clone, Syntax)]
+enum HygieneStmt<I>
+where
+    I: LangInput,
+{
+    Hygiene(Hygiene<I>, #[syntax(default)] Option<I>),
+}
+
+#[test]
+fn test_hygiene() {
+    let stmt = TS::from("1=2").parse::<HygieneStmt<_>>().unwrap();
+
+    assert_eq!(
+        stmt,
+        HygieneStmt::Hygiene(
+            Hygiene {
+                input: Digits(TS::from("1")),
+                parser: Assign(TS::from((1, "="))),
+                value: Spanned::new(2, Span::Range(2..3)),
+                start: 3,
+            },
+            None
+        )
+    );
+    assert_eq!(stmt.to_span(), Span::Range(0..3));
+}
+
+/// Spaces and newlines.
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(token = |c: u8| c == b' ' || c == b'\n')]
+struct Ws<I>(I)
+where
+    I: LangInput;
+
+/// token `.`
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(char = b'.')]
+struct Dot<I>(I)
+where
+    I: LangInput;
+
+/// `digits.digits`, the tokens stay adjacent.
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(no_trivia)]
+struct Float<I>
+where
+    I: LangInput,
+{
+    int: Digits<I>,
+    dot: Dot<I>,
+    frac: Digits<I>,
+}
+
+#[derive(Debug, PartialEq, Clone, Syntax)]
+struct Binding<I>
+where
+    I: LangInput,
+{
+    kw: KwLet<I>,
+    assign: Assign<I>,
+    value: Float<I>,
+}
+
+#[derive(Debug, PartialEq, Clone, Syntax)]
+#[syntax(trivia = Ws<I>)]
+struct Bindings<I>(Vec<Binding<I>>)
+where
+    I: LangInput;
+
+#[test]
+fn test_trivia() {
+    let mut input = Trivia::new(TS::from(" let = 1.5\nlet=2.25 "));
+    let bindings = input.parse::<Bindings<_>>().unwrap();
+
+    assert_eq!(bindings.0.len(), 2);
+    assert_eq!(bindings.0[0].kw.to_span(), Span::Range(1..4));
+    assert_eq!(bindings.0[0].value.to_span(), Span::Range(7..10));
+    assert_eq!(bindings.0[1].value.to_span(), Span::Range(15..19));
+    // the trailing trivia is skipped by the failed third `Binding`, which restores it.
+    assert_eq!(input.as_str(), " ");
+
+    // the previous skipper is restored.
+    assert!(input.replace_trivia(None).is_none());
+
+    // other wrappers go inside `Trivia`.
+    let mut input = Trivia::new(Memo::new(Furthest::new(TS::from("let = 1.5 let = 2.5"))));
+    assert_eq!(input.parse::<Bindings<_>>().unwrap().0.len(), 2);
+    assert!(input.is_empty());
+
+    // `no_trivia` keeps the tokens of `Float` adjacent.
+    let mut input = Trivia::new(TS::from("let = 1 .5"));
+    assert!(input.parse::<Bindings<_>>().unwrap().0.is_empty());
+
+    // a plain input doesn't skip trivia.
+    assert!(
+        TS::from("let = 1.5")
+            .parse::<Bindings<_>>()
+            .unwrap()
+            .0
+            .is_empty()
+    );
+    assert_eq!(
+        TS::from("let=1.5").parse::<Bindings<_>>().unwrap().0.len(),
+        1
+    );
+}
+
+/// `digits(digits)!` or `digits`, the first variant recovers its arguments.
+#[derive(Debug, PartialEq, Clone, Syntax)]
+enum Call<I>
+where
+    I: LangInput,
+{
+    Call(
+        Digits<I>,
+        Delimiter<ParenStart<I>, ParenEnd<I>, Recovered<Digits<I>>>,
+        Bang<I>,
+    ),
+    Number(Digits<I>),
+}
+
+#[test]
+fn test_recovery_backtrack() {
+    // the `Call` variant recovers `x` and then fails, its error is dropped with it.
+    let mut input = Recovery::new(TS::from("1(x)"));
+    assert_eq!(
+        input.parse::<Call<_>>(),
+        Ok(Call::Number(Digits(Recovery::new(TS::from("1")))))
+    );
+    assert_eq!(input.as_inner(), &TS::from((1, "(x)")));
+    assert!(input.take_errors().is_empty());
+
+    let mut input = Recovery::new(TS::from("1(x)!"));
+    assert!(matches!(input.parse::<Call<_>>(), Ok(Call::Call(..))));
+    assert_eq!(
+        input.take_errors(),
+        vec![Kind::TakeWhileRange(ControlFlow::Fatal, Span::Range(2..2))]
+    );
+}
@@ -75,4 +75,58 @@ impl ParseError for RegexError {
             }
         }
     }
+
+    fn context(self, label: &'static str) -> Self {
+        match self {
+            RegexError::Other(kind) => RegexError::Other(kind.context(label)),
+            err => err,
+        }
+    }
+
+    fn contexts(&self) -> Vec<&'static str> {
+        match self {
+            RegexError::Other(kind) => kind.contexts(),
+            RegexError::Compile(..) => vec![],
+        }
+    }
+
+    fn root_message(&self) -> String {
+        match self {
+            RegexError::Other(kind) => format!("{:?}", kind.root()),
+            err => err.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parserc::{
+        Span,
+        diagnostic::{Diagnostic, Renderer},
+        syntax::InputSyntaxExt,
+    };
+
+    use crate::{errors::RegexError, input::TokenStream, pattern::Pattern};
+
+    #[test]
+    fn test_diagnostic() {
+        let source = "(abc";
+        let err: RegexError = TokenStream::from(source).parse::<Pattern<_>>().unwrap_err();
+
+        let diagnostic = Diagnostic::from_error(&err)
+            .with_message("expected `)`")
+            .with_label(Span::Range(0..1), "unclosed delimiter");
+
+        assert_eq!(
+            Renderer::plain().render(&diagnostic, "regex", source),
+            "\
+error: Next(Fatal, RangeFrom(4..))
+ --> regex:1:5
+  |
+1 | (abc
+  |     ^ expected `)`
+  | - unclosed delimiter
+"
+        );
+    }
 }
@@ -24,6 +24,7 @@ pub trait PatternInput:
 }
 
 /// `Input` for compute language parsing.
+#[allow(clippy::derived_hash_with_manual_eq)]
 #[derive(Eq, PartialOrd, Ord, Hash)]
 pub struct TokenStream<'a> {
     /// offset in the whole token stream.
@@ -59,7 +59,7 @@ where
 
                 let from = sequnce.iter().next().unwrap();
 
-                if !(from < to) {
+                if from >= to {
                     return Err(RegexError::Compile(
                         CompileError::CharRange,
                         ControlFlow::Fatal,
@@ -77,7 +77,7 @@ where
             }
         }
 
-        return Ok(Self::Sequnce(sequnce));
+        Ok(Self::Sequnce(sequnce))
     }
 
     #[inline]
@@ -94,12 +94,13 @@ where
     }
 }
 
+/// Body of character class: an optional negation `^` followed by class items.
+pub type ClassBody<I> = (Option<Caret<I>>, Vec<ClassChars<I>>);
+
 /// Character class.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Syntax)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-pub struct Class<I>(
-    pub Delimiter<BracketStart<I>, BracketEnd<I>, (Option<Caret<I>>, Vec<ClassChars<I>>)>,
-)
+pub struct Class<I>(pub Delimiter<BracketStart<I>, BracketEnd<I>, ClassBody<I>>)
 where
     I: PatternInput;
 
@@ -39,10 +39,11 @@ where
 
         let n = Digits::parse(input).map_err(CompileError::Repeat.map_fatal())?;
 
-        if let Some(_) = next(',')
+        if next(',')
             .ok()
             .parse(input)
             .map_err(CompileError::Repeat.map_fatal())?
+            .is_some()
         {
             if let Some(m) = Digits::into_parser()
                 .ok()
@@ -164,9 +164,8 @@ where
 
 #[inline]
 pub(super) fn is_token_char(c: char) -> bool {
-    match c {
-        '\\' | '|' | '^' | '$' | '*' | '+' | '-' | '?' | '{' | '[' | ']' | '.' | '=' | '('
-        | ')' => true,
-        _ => false,
-    }
+    matches!(
+        c,
+        '\\' | '|' | '^' | '$' | '*' | '+' | '-' | '?' | '{' | '[' | ']' | '.' | '=' | '(' | ')'
+    )
 }
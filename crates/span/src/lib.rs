@@ -6,6 +6,15 @@ use std::{
     ops::{self, Range, RangeFrom, RangeFull, RangeTo},
 };
 
+mod line;
+pub use line::*;
+
+mod source;
+pub use source::*;
+
+mod spanned;
+pub use spanned::*;
+
 /// A region of source code.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
@@ -128,71 +137,194 @@ where
         }
     }
 
-    /// Union two range.
+    /// Returns the lower and upper bounds, `None` means unbounded.
+    ///
+    /// Returns `None` for `Span::None`.
+    #[inline]
+    fn bounds(&self) -> Option<(Option<Idx>, Option<Idx>)> {
+        match self {
+            Span::None => None,
+            Span::Range(range) => Some((Some(range.start), Some(range.end))),
+            Span::RangeFrom(range_from) => Some((Some(range_from.start), None)),
+            Span::RangeTo(range_to) => Some((None, Some(range_to.end))),
+            Span::RangeFull => Some((None, None)),
+        }
+    }
+
+    /// Create a span from lower and upper bounds, `None` means unbounded.
+    #[inline]
+    fn from_bounds(lower: Option<Idx>, upper: Option<Idx>) -> Self {
+        match (lower, upper) {
+            (Some(start), Some(end)) => Span::Range(start..end),
+            (Some(start), None) => Span::RangeFrom(start..),
+            (None, Some(end)) => Span::RangeTo(..end),
+            (None, None) => Span::RangeFull,
+        }
+    }
+
+    /// Returns the smallest span that covers both spans.
+    ///
+    /// `Span::None` is the identity, an empty range still counts as a position,
+    /// and an unbounded side of either span stays unbounded.
     #[inline]
     pub fn union(&self, other: &Self) -> Self {
-        match (self, other) {
-            (Span::None, Span::None) => Span::None,
-            (Span::None, Span::Range(_)) => other.clone(),
-            (Span::None, Span::RangeFrom(_)) => other.clone(),
-            (Span::None, Span::RangeTo(_)) => other.clone(),
-            (Span::Range(_), Span::None) => self.clone(),
-            (Span::Range(range), Span::Range(other_range)) => {
-                let start = cmp::min(range.start, other_range.start);
-                let end = cmp::max(range.end, other_range.end);
+        let Some((lower, upper)) = self.bounds() else {
+            return other.clone();
+        };
+
+        let Some((other_lower, other_upper)) = other.bounds() else {
+            return self.clone();
+        };
+
+        Self::from_bounds(
+            lower.zip(other_lower).map(|(lhs, rhs)| cmp::min(lhs, rhs)),
+            upper.zip(other_upper).map(|(lhs, rhs)| cmp::max(lhs, rhs)),
+        )
+    }
+}
 
-                Span::Range(start..end)
-            }
-            (Span::Range(range), Span::RangeFrom(range_from)) => {
-                let start = cmp::min(range.start, range_from.start);
+/// Set operations, a span is the set of offsets `start <= offset < end`,
+/// an open start bound means `0`, an open end bound means infinite.
+impl Span<usize> {
+    /// Returns the number of offsets, `None` if the span is unbounded above.
+    #[inline]
+    pub fn len(&self) -> Option<usize> {
+        match self {
+            Span::None => Some(0),
+            Span::Range(range) => Some(range.end.saturating_sub(range.start)),
+            Span::RangeTo(range_to) => Some(range_to.end),
+            Span::RangeFrom(_) | Span::RangeFull => None,
+        }
+    }
 
-                Span::RangeFrom(start..)
-            }
-            (Span::Range(range), Span::RangeTo(range_to)) => {
-                let end = cmp::max(range.end, range_to.end);
+    /// Returns true if the span contains no offset.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
 
-                Span::RangeTo(..end)
-            }
-            (Span::RangeFrom(_), Span::None) => self.clone(),
-            (Span::RangeFrom(range_from), Span::Range(range)) => {
-                if range_from.start > range.end {
-                    Span::None
-                } else {
-                    Span::Range(range_from.start..range.end)
-                }
-            }
-            (Span::RangeFrom(range_from), Span::RangeFrom(other_range_from)) => {
-                let start = cmp::min(range_from.start, other_range_from.start);
-                Span::RangeFrom(start..)
+    /// Returns true if `offset` is in this span.
+    #[inline]
+    pub fn contains_offset(&self, offset: usize) -> bool {
+        match self.bounds() {
+            None => false,
+            Some((lower, upper)) => {
+                lower.unwrap_or(0) <= offset && upper.is_none_or(|upper| offset < upper)
             }
-            (Span::RangeFrom(_), Span::RangeTo(_)) => Span::RangeFull,
-            (Span::RangeTo(_), Span::None) => self.clone(),
-            (Span::RangeTo(range_to), Span::Range(range)) => {
-                let end = cmp::max(range_to.end, range.end);
+        }
+    }
 
-                Span::RangeTo(..end)
-            }
-            (Span::RangeTo(range_to), Span::RangeFrom(range_from)) => {
-                if range_to.end < range_from.start {
-                    return Span::None;
-                }
+    /// Returns true if every offset of `other` is in this span, an empty span is in any span.
+    pub fn contains(&self, other: &Self) -> bool {
+        if other.is_empty() {
+            return true;
+        }
 
-                return Span::RangeFull;
-            }
-            (Span::RangeTo(range_to), Span::RangeTo(other_range_to)) => {
-                let end = cmp::max(range_to.end, other_range_to.end);
+        let (Some((lower, upper)), Some((other_lower, other_upper))) =
+            (self.bounds(), other.bounds())
+        else {
+            return false;
+        };
 
-                Span::RangeTo(..end)
-            }
-            (Span::None, Span::RangeFull) => Span::RangeFull,
-            (Span::Range(_), Span::RangeFull) => Span::RangeFull,
-            (Span::RangeFrom(_), Span::RangeFull) => Span::RangeFull,
-            (Span::RangeTo(_), Span::RangeFull) => Span::RangeFull,
-            (Span::RangeFull, Span::None) => Span::RangeFull,
-            (Span::RangeFull, Span::Range(_)) => Span::RangeFull,
-            (Span::RangeFull, Span::RangeFrom(_)) => Span::RangeFull,
-            (Span::RangeFull, Span::RangeTo(_)) => Span::RangeFull,
-            (Span::RangeFull, Span::RangeFull) => Span::RangeFull,
+        let upper_contains = match (upper, other_upper) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(upper), Some(other_upper)) => other_upper <= upper,
+        };
+
+        lower.unwrap_or(0) <= other_lower.unwrap_or(0) && upper_contains
+    }
+
+    /// Returns the offsets in both spans, `Span::None` if there is none.
+    pub fn intersect(&self, other: &Self) -> Self {
+        let (Some((lower, upper)), Some((other_lower, other_upper))) =
+            (self.bounds(), other.bounds())
+        else {
+            return Span::None;
+        };
+
+        // `None` is the smallest `Option`, which is also the unbounded lower bound.
+        let lower = cmp::max(lower, other_lower);
+
+        let upper = match (upper, other_upper) {
+            (Some(upper), Some(other_upper)) => Some(cmp::min(upper, other_upper)),
+            (upper, None) => upper,
+            (None, other_upper) => other_upper,
+        };
+
+        let span = Self::from_bounds(lower, upper);
+
+        if span.is_empty() { Span::None } else { span }
+    }
+
+    /// Returns the offsets of this span not in `other`, as the parts before and after `other`.
+    ///
+    /// Each part is `Span::None` if it is empty.
+    pub fn difference(&self, other: &Self) -> (Self, Self) {
+        if self.is_empty() {
+            return (Span::None, Span::None);
+        }
+
+        let Some((lower, upper)) = other.bounds().filter(|_| !other.is_empty()) else {
+            return (self.clone(), Span::None);
+        };
+
+        let before = match lower {
+            Some(lower) => self.intersect(&Span::RangeTo(..lower)),
+            None => Span::None,
+        };
+
+        let after = match upper {
+            Some(upper) => self.intersect(&Span::RangeFrom(upper..)),
+            None => Span::None,
+        };
+
+        (before, after)
+    }
+
+    /// Move the bounded sides by `delta`, saturating at `0` and `usize::MAX`.
+    ///
+    /// An open start bound stays at `0`.
+    pub fn shift(&self, delta: isize) -> Self {
+        let shift = |offset: usize| offset.saturating_add_signed(delta);
+
+        match self {
+            Span::None => Span::None,
+            Span::Range(range) => Span::Range(shift(range.start)..shift(range.end)),
+            Span::RangeFrom(range_from) => Span::RangeFrom(shift(range_from.start)..),
+            Span::RangeTo(range_to) => Span::RangeTo(..shift(range_to.end)),
+            Span::RangeFull => Span::RangeFull,
+        }
+    }
+
+    /// Convert to a `Range` within a source of `len` bytes, keeping empty ranges as positions.
+    ///
+    /// Returns `Span::None` for `Span::None`.
+    pub fn clamp_to(&self, len: usize) -> Self {
+        self.to_range(len).map_or(Span::None, Span::Range)
+    }
+
+    /// Convert to a `Range` within a source of `len` bytes, open bounds are resolved to `0` and `len`.
+    ///
+    /// Returns `None` for `Span::None`.
+    pub fn to_range(&self, len: usize) -> Option<Range<usize>> {
+        let (lower, upper) = self.bounds()?;
+
+        let end = cmp::min(upper.unwrap_or(len), len);
+        let start = cmp::min(lower.unwrap_or(0), end);
+
+        Some(start..end)
+    }
+}
+
+impl<Idx> TryFrom<Span<Idx>> for Range<Idx> {
+    type Error = Span<Idx>;
+
+    /// Only `Span::Range` converts, other variants are returned back.
+    fn try_from(value: Span<Idx>) -> Result<Self, Self::Error> {
+        match value {
+            Span::Range(range) => Ok(range),
+            span => Err(span),
         }
     }
 }
@@ -266,7 +398,12 @@ mod tests {
     fn test_union() {
         assert_eq!(
             Span::RangeTo(..14).union(&Span::RangeFrom(18..)),
-            Span::None
+            Span::RangeFull
+        );
+
+        assert_eq!(
+            Span::RangeFrom(18..).union(&Span::Range(2..4)),
+            Span::RangeFrom(2..)
         );
 
         assert_eq!(
@@ -294,4 +431,199 @@ mod tests {
             Span::RangeTo(..18)
         );
     }
+
+    /// Offsets checked by the property tests, beyond every bound of [`spans`].
+    const OFFSETS: Range<usize> = 0..10;
+
+    /// Every variant with bounds in `0..=6`, including reversed ranges.
+    fn spans() -> Vec<Span<usize>> {
+        let mut spans = vec![Span::None, Span::RangeFull];
+
+        for start in 0..=6 {
+            spans.push(Span::RangeFrom(start..));
+            spans.push(Span::RangeTo(..start));
+
+            for end in 0..=6 {
+                spans.push(Span::Range(start..end));
+            }
+        }
+
+        spans
+    }
+
+    /// The set model of `span` within [`OFFSETS`].
+    fn offsets(span: &Span<usize>) -> Vec<usize> {
+        OFFSETS
+            .filter(|offset| match span {
+                Span::None => false,
+                Span::Range(range) => range.contains(offset),
+                Span::RangeFrom(range_from) => range_from.contains(offset),
+                Span::RangeTo(range_to) => range_to.contains(offset),
+                Span::RangeFull => true,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_len() {
+        for span in spans() {
+            let bounded = !matches!(span, Span::RangeFrom(_) | Span::RangeFull);
+
+            assert_eq!(
+                span.len(),
+                bounded.then(|| offsets(&span).len()),
+                "{:?}",
+                span
+            );
+            assert_eq!(span.is_empty(), offsets(&span).is_empty(), "{:?}", span);
+
+            for offset in OFFSETS {
+                assert_eq!(
+                    span.contains_offset(offset),
+                    offsets(&span).contains(&offset),
+                    "{:?} {}",
+                    span,
+                    offset
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_set_operations() {
+        for lhs in spans() {
+            for rhs in spans() {
+                let (lhs_offsets, rhs_offsets) = (offsets(&lhs), offsets(&rhs));
+
+                let intersect = lhs.intersect(&rhs);
+
+                assert_eq!(
+                    offsets(&intersect),
+                    lhs_offsets
+                        .iter()
+                        .copied()
+                        .filter(|offset| rhs_offsets.contains(offset))
+                        .collect::<Vec<_>>(),
+                    "{:?} & {:?}",
+                    lhs,
+                    rhs
+                );
+                assert_eq!(intersect.is_empty(), intersect == Span::None);
+                assert_eq!(intersect, rhs.intersect(&lhs));
+
+                assert_eq!(
+                    lhs.contains(&rhs),
+                    rhs_offsets
+                        .iter()
+                        .all(|offset| lhs_offsets.contains(offset)),
+                    "{:?} contains {:?}",
+                    lhs,
+                    rhs
+                );
+
+                let union = lhs.union(&rhs);
+
+                assert_eq!(union, rhs.union(&lhs));
+                assert!(union.contains(&lhs) && union.contains(&rhs));
+
+                // the union of non-empty spans is the convex hull, without gaps.
+                if !lhs.is_empty() && !rhs.is_empty() && union.len().is_some() {
+                    let all = [lhs_offsets.clone(), rhs_offsets.clone()].concat();
+                    let min = *all.iter().min().unwrap();
+                    let max = *all.iter().max().unwrap();
+
+                    assert_eq!(
+                        offsets(&union),
+                        (min..=max).collect::<Vec<_>>(),
+                        "{:?} | {:?}",
+                        lhs,
+                        rhs
+                    );
+                }
+
+                let (before, after) = lhs.difference(&rhs);
+
+                assert_eq!(
+                    [offsets(&before), offsets(&after)].concat(),
+                    lhs_offsets
+                        .iter()
+                        .copied()
+                        .filter(|offset| !rhs_offsets.contains(offset))
+                        .collect::<Vec<_>>(),
+                    "{:?} - {:?}",
+                    lhs,
+                    rhs
+                );
+                assert!(before.is_empty() == (before == Span::None));
+                assert!(after.is_empty() == (after == Span::None));
+            }
+        }
+    }
+
+    #[test]
+    fn test_shift_and_clamp() {
+        for span in spans() {
+            for delta in -3..=3 {
+                let shifted = span.shift(delta);
+
+                for offset in OFFSETS.filter(|offset| *offset >= 3) {
+                    let moved = offset.saturating_add_signed(delta);
+
+                    // an open start bound stays at `0`, only offsets after it move.
+                    if !matches!(span, Span::RangeTo(_) | Span::RangeFull) {
+                        assert_eq!(
+                            span.contains_offset(offset),
+                            shifted.contains_offset(moved),
+                            "{:?} >> {}",
+                            span,
+                            delta
+                        );
+                    }
+                }
+            }
+
+            for len in 0..=8 {
+                let clamped = span.clamp_to(len);
+                let range = span.to_range(len);
+
+                assert_eq!(clamped == Span::None, span == Span::None);
+                assert_eq!(
+                    range.clone().map(Span::Range).unwrap_or(Span::None),
+                    clamped
+                );
+
+                if let Some(range) = range {
+                    assert!(range.start <= range.end && range.end <= len);
+                    assert_eq!(
+                        offsets(&clamped),
+                        offsets(&span)
+                            .into_iter()
+                            .filter(|offset| *offset < len)
+                            .collect::<Vec<_>>(),
+                        "{:?} clamp {}",
+                        span,
+                        len
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_range_conversion() {
+        assert_eq!(Range::try_from(Span::Range(1..4)), Ok(1..4));
+        assert_eq!(
+            Range::try_from(Span::RangeFrom(1..)),
+            Err(Span::RangeFrom(1..))
+        );
+        assert_eq!(Span::from(1..4), Span::Range(1..4));
+
+        assert_eq!(Span::RangeFrom(3..).clamp_to(2), Span::Range(2..2));
+        assert_eq!(Span::RangeTo(..3).shift(2), Span::RangeTo(..5));
+        assert_eq!(Span::Range(1..3).shift(-2), Span::Range(0..1));
+        assert_eq!(
+            Span::Range(0..10).difference(&Span::Range(3..5)),
+            (Span::Range(0..3), Span::Range(5..10))
+        );
+    }
 }
@@ -0,0 +1,343 @@
+//! Line/column index of source code.
+
+use std::{fmt::Display, ops::Range};
+
+use crate::Span;
+
+/// The unit a column is counted in.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
+pub enum ColumnUnit {
+    /// UTF-8 bytes.
+    #[default]
+    Utf8,
+    /// UTF-16 code units, used by `LSP` clients.
+    Utf16,
+    /// Unicode scalar values.
+    Char,
+}
+
+/// A zero-based line/column position.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Default)]
+pub struct Position {
+    /// Zero-based line number.
+    pub line: usize,
+    /// Zero-based column number, counted in a [`ColumnUnit`].
+    pub column: usize,
+}
+
+impl Position {
+    /// Create a new position.
+    #[inline]
+    pub fn new(line: usize, column: usize) -> Self {
+        Self { line, column }
+    }
+}
+
+/// Displays as one-based `line:column`.
+impl Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line + 1, self.column + 1)
+    }
+}
+
+/// A non-ASCII char in a line.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+struct WideChar {
+    /// Byte offset from the line start.
+    column: usize,
+    /// Length in UTF-8 bytes.
+    len_utf8: usize,
+    /// Length in UTF-16 code units.
+    len_utf16: usize,
+}
+
+impl WideChar {
+    /// Returns the length counted in `unit`.
+    #[inline]
+    fn len(&self, unit: ColumnUnit) -> usize {
+        match unit {
+            ColumnUnit::Utf8 => self.len_utf8,
+            ColumnUnit::Utf16 => self.len_utf16,
+            ColumnUnit::Char => 1,
+        }
+    }
+}
+
+/// An index maps byte offsets of the source code to line/column positions and back.
+///
+/// Lines are separated by `\n`, a `\r` before it belongs to the line.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct LineIndex {
+    /// Byte offset of each line start, the first one is always `0`.
+    line_starts: Vec<usize>,
+    /// Non-ASCII chars of each line.
+    wide_chars: Vec<Vec<WideChar>>,
+    /// Source code length in bytes.
+    len: usize,
+}
+
+impl LineIndex {
+    /// Build the index from the source code.
+    pub fn new(source: &str) -> Self {
+        let mut line_starts = vec![0];
+        let mut wide_chars = vec![vec![]];
+
+        for (offset, c) in source.char_indices() {
+            if c == '\n' {
+                line_starts.push(offset + 1);
+                wide_chars.push(vec![]);
+            } else if !c.is_ascii() {
+                let line_start = line_starts[line_starts.len() - 1];
+
+                wide_chars[line_starts.len() - 1].push(WideChar {
+                    column: offset - line_start,
+                    len_utf8: c.len_utf8(),
+                    len_utf16: c.len_utf16(),
+                });
+            }
+        }
+
+        Self {
+            line_starts,
+            wide_chars,
+            len: source.len(),
+        }
+    }
+
+    /// Returns the source code length in bytes.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the source code is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of lines, an empty source has one line.
+    #[inline]
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// Returns the byte range of `line`, including its `\n`.
+    #[inline]
+    pub fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = *self.line_starts.get(line)?;
+        let end = self.line_starts.get(line + 1).copied().unwrap_or(self.len);
+
+        Some(start..end)
+    }
+
+    /// Map a byte `offset` to its position, returns `None` if `offset` is out of the source code.
+    ///
+    /// An offset inside a multi-byte char maps to the position of that char.
+    pub fn position(&self, offset: usize, unit: ColumnUnit) -> Option<Position> {
+        if offset > self.len {
+            return None;
+        }
+
+        // the last line whose start <= offset.
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let bytes = offset - self.line_starts[line];
+        let wide_chars = &self.wide_chars[line];
+
+        // snap down to the start of the char containing `offset`.
+        let bytes = wide_chars
+            .iter()
+            .find(|c| c.column < bytes && bytes < c.column + c.len_utf8)
+            .map_or(bytes, |c| c.column);
+
+        let column = wide_chars
+            .iter()
+            .take_while(|c| c.column < bytes)
+            .fold(bytes, |column, c| column - c.len_utf8 + c.len(unit));
+
+        Some(Position { line, column })
+    }
+
+    /// Map a `position` back to its byte offset.
+    ///
+    /// A column inside a multi-byte char maps to the offset of that char.
+    /// Returns `None` if the line doesn't exist or the column is beyond the line end.
+    pub fn offset(&self, position: Position, unit: ColumnUnit) -> Option<usize> {
+        let range = self.line_range(position.line)?;
+
+        let mut column = position.column;
+        let mut offset = 0;
+
+        for c in &self.wide_chars[position.line] {
+            // ASCII chars before `c` are one byte and one unit each.
+            let ascii = c.column - offset;
+
+            if column < ascii + c.len(unit) {
+                // snap a column inside `c` down to its start.
+                column = column.min(ascii);
+                break;
+            }
+
+            column -= ascii + c.len(unit);
+            offset = c.column + c.len_utf8;
+        }
+
+        let offset = range.start + offset + column;
+
+        // the last valid column of a line is its `\n`.
+        let end = if position.line + 1 < self.line_count() {
+            range.end - 1
+        } else {
+            range.end
+        };
+
+        if offset > end {
+            return None;
+        }
+
+        Some(offset)
+    }
+
+    /// Map a `span` to its start and end positions.
+    ///
+    /// Open bounds map to the start or end of the source code, returns `None` for `Span::None`.
+    pub fn span(&self, span: &Span<usize>, unit: ColumnUnit) -> Option<(Position, Position)> {
+        let (start, end) = match span {
+            Span::None => return None,
+            Span::Range(range) => (range.start, range.end),
+            Span::RangeFrom(range) => (range.start, self.len),
+            Span::RangeTo(range) => (0, range.end),
+            Span::RangeFull => (0, self.len),
+        };
+
+        Some((self.position(start, unit)?, self.position(end, unit)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position() {
+        let index = LineIndex::new("ab\ncd\r\n\nef");
+
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.line_range(1), Some(3..7));
+        assert_eq!(
+            index.position(0, ColumnUnit::Utf8),
+            Some(Position::new(0, 0))
+        );
+        assert_eq!(
+            index.position(2, ColumnUnit::Utf8),
+            Some(Position::new(0, 2))
+        );
+        assert_eq!(
+            index.position(3, ColumnUnit::Utf8),
+            Some(Position::new(1, 0))
+        );
+        assert_eq!(
+            index.position(7, ColumnUnit::Utf8),
+            Some(Position::new(2, 0))
+        );
+        assert_eq!(
+            index.position(10, ColumnUnit::Utf8),
+            Some(Position::new(3, 2))
+        );
+        assert_eq!(index.position(11, ColumnUnit::Utf8), None);
+
+        for offset in 0..=10 {
+            let position = index.position(offset, ColumnUnit::Utf8).unwrap();
+            assert_eq!(index.offset(position, ColumnUnit::Utf8), Some(offset));
+        }
+
+        assert_eq!(index.offset(Position::new(0, 3), ColumnUnit::Utf8), None);
+        assert_eq!(index.offset(Position::new(4, 0), ColumnUnit::Utf8), None);
+    }
+
+    #[test]
+    fn test_wide_chars() {
+        // `é` is 2 bytes/1 utf-16 unit, `𝕏` is 4 bytes/2 utf-16 units.
+        let source = "x\naé𝕏b";
+        let index = LineIndex::new(source);
+
+        let b = source.find('b').unwrap();
+
+        assert_eq!(
+            index.position(b, ColumnUnit::Utf8),
+            Some(Position::new(1, 7))
+        );
+        assert_eq!(
+            index.position(b, ColumnUnit::Utf16),
+            Some(Position::new(1, 4))
+        );
+        assert_eq!(
+            index.position(b, ColumnUnit::Char),
+            Some(Position::new(1, 3))
+        );
+
+        for unit in [ColumnUnit::Utf8, ColumnUnit::Utf16, ColumnUnit::Char] {
+            for (offset, _) in source.char_indices().chain([(source.len(), ' ')]) {
+                let position = index.position(offset, unit).unwrap();
+                assert_eq!(index.offset(position, unit), Some(offset), "{:?}", unit);
+            }
+        }
+
+        // offsets inside a char map to its start.
+        let index = LineIndex::new("é𝕏");
+
+        assert_eq!(
+            index.position(1, ColumnUnit::Char),
+            Some(Position::new(0, 0))
+        );
+        assert_eq!(
+            index.position(4, ColumnUnit::Utf16),
+            Some(Position::new(0, 1))
+        );
+        assert_eq!(
+            index.position(5, ColumnUnit::Utf8),
+            Some(Position::new(0, 2))
+        );
+
+        // columns inside a char map to its start.
+        assert_eq!(index.offset(Position::new(0, 1), ColumnUnit::Utf8), Some(0));
+        assert_eq!(index.offset(Position::new(0, 4), ColumnUnit::Utf8), Some(2));
+        assert_eq!(
+            index.offset(Position::new(0, 2), ColumnUnit::Utf16),
+            Some(2)
+        );
+
+        let source = "😀a";
+        let index = LineIndex::new(source);
+
+        let offset = index
+            .offset(Position::new(0, 1), ColumnUnit::Utf16)
+            .unwrap();
+        assert_eq!(offset, 0);
+        assert_eq!(&source[offset..], "😀a");
+        assert_eq!(
+            index.offset(Position::new(0, 2), ColumnUnit::Utf16),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn test_span() {
+        let index = LineIndex::new("fn main() {\n    let x;\n}");
+
+        assert_eq!(
+            index.span(&Span::Range(16..22), ColumnUnit::Utf8),
+            Some((Position::new(1, 4), Position::new(1, 10)))
+        );
+        assert_eq!(
+            index.span(&Span::RangeFrom(12..), ColumnUnit::Utf8),
+            Some((Position::new(1, 0), Position::new(2, 1)))
+        );
+        assert_eq!(index.span(&Span::None, ColumnUnit::Utf8), None);
+        assert_eq!(Position::new(1, 4).to_string(), "2:5");
+    }
+}
@@ -0,0 +1,232 @@
+//! Multi-file source map.
+
+use std::{
+    fmt::Display,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use crate::{ColumnUnit, LineIndex, Position, Span};
+
+/// Identifier of a file registered in a [`SourceMap`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct FileId(pub usize);
+
+/// A file registered in a [`SourceMap`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct SourceFile {
+    id: FileId,
+    path: PathBuf,
+    source: String,
+    /// Global offset of the first byte.
+    start: usize,
+    line_index: LineIndex,
+}
+
+impl SourceFile {
+    /// Returns the file identifier.
+    #[inline]
+    pub fn id(&self) -> FileId {
+        self.id
+    }
+
+    /// Returns the file path.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the file content.
+    #[inline]
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the global offset range of this file.
+    #[inline]
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.start + self.source.len()
+    }
+
+    /// Returns the line index of this file.
+    #[inline]
+    pub fn line_index(&self) -> &LineIndex {
+        &self.line_index
+    }
+
+    /// Returns `(global start offset, source)`, which constructs an input positioned in this file,
+    /// e.g. `TokenStream::from(file.as_input())`.
+    #[inline]
+    pub fn as_input(&self) -> (usize, &str) {
+        (self.start, &self.source)
+    }
+
+    /// Convert a global `offset` into the local offset of this file.
+    #[inline]
+    pub fn local_offset(&self, offset: usize) -> Option<usize> {
+        if self.range().contains(&offset) || offset == self.range().end {
+            Some(offset - self.start)
+        } else {
+            None
+        }
+    }
+
+    /// Map a global `offset` to its line/column position in this file.
+    #[inline]
+    pub fn position(&self, offset: usize, unit: ColumnUnit) -> Option<Position> {
+        self.line_index.position(self.local_offset(offset)?, unit)
+    }
+}
+
+/// A location resolved by [`SourceMap::lookup`].
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Location<'a> {
+    /// The file contains the offset.
+    pub file: FileId,
+    /// Path of the file.
+    pub path: &'a Path,
+    /// Offset from the start of the file.
+    pub offset: usize,
+}
+
+/// Displays as `path:offset`.
+impl<'a> Display for Location<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.path.display(), self.offset)
+    }
+}
+
+/// A set of source files, each one occupies its own global offset range.
+///
+/// Spans of one compilation unit can refer to any registered file and still be told apart.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone)]
+pub struct SourceMap {
+    files: Vec<SourceFile>,
+}
+
+impl SourceMap {
+    /// Create an empty source map.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a file and returns its identifier.
+    ///
+    /// Files are separated by a one byte gap, so the end offset of a file never starts the next one.
+    pub fn add(&mut self, path: impl Into<PathBuf>, source: impl Into<String>) -> FileId {
+        let id = FileId(self.files.len());
+        let start = self.files.last().map_or(0, |file| file.range().end + 1);
+        let source = source.into();
+
+        self.files.push(SourceFile {
+            id,
+            path: path.into(),
+            line_index: LineIndex::new(&source),
+            source,
+            start,
+        });
+
+        id
+    }
+
+    /// Returns the registered file by `id`.
+    #[inline]
+    pub fn get(&self, id: FileId) -> Option<&SourceFile> {
+        self.files.get(id.0)
+    }
+
+    /// Returns all registered files.
+    #[inline]
+    pub fn files(&self) -> &[SourceFile] {
+        &self.files
+    }
+
+    /// Returns the file contains the global `offset`, the end offset of a file belongs to it.
+    pub fn file(&self, offset: usize) -> Option<&SourceFile> {
+        // the last file whose start <= offset.
+        let index = self
+            .files
+            .partition_point(|file| file.start <= offset)
+            .checked_sub(1)?;
+
+        let file = &self.files[index];
+
+        file.local_offset(offset).map(|_| file)
+    }
+
+    /// Resolve a global `offset` to its file and local offset.
+    pub fn lookup(&self, offset: usize) -> Option<Location<'_>> {
+        let file = self.file(offset)?;
+
+        Some(Location {
+            file: file.id,
+            path: &file.path,
+            offset: offset - file.start,
+        })
+    }
+
+    /// Resolve a global `span` to its file and the local span in that file.
+    ///
+    /// Returns `None` if the span is unbounded or crosses files.
+    pub fn resolve(&self, span: &Span<usize>) -> Option<(FileId, Span<usize>)> {
+        let Span::Range(range) = span else {
+            return None;
+        };
+
+        let file = self.file(range.start)?;
+
+        if range.end > file.range().end {
+            return None;
+        }
+
+        Some((
+            file.id,
+            Span::Range(range.start - file.start..range.end - file.start),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_map() {
+        let mut map = SourceMap::new();
+
+        let main = map.add("main.rs", "fn main() {}\n");
+        let lib = map.add("lib.rs", "mod a;\nmod b;");
+
+        assert_eq!(map.get(main).unwrap().range(), 0..13);
+        assert_eq!(map.get(lib).unwrap().as_input(), (14, "mod a;\nmod b;"));
+
+        assert_eq!(
+            map.lookup(3),
+            Some(Location {
+                file: main,
+                path: Path::new("main.rs"),
+                offset: 3
+            })
+        );
+        assert_eq!(map.lookup(13).unwrap().file, main);
+        assert_eq!(map.lookup(14).unwrap().to_string(), "lib.rs:0");
+        assert_eq!(map.lookup(27).unwrap().offset, 13);
+        assert_eq!(map.lookup(28), None);
+
+        assert_eq!(
+            map.get(lib).unwrap().position(25, ColumnUnit::Utf8),
+            Some(Position::new(1, 4))
+        );
+
+        assert_eq!(
+            map.resolve(&Span::Range(21..27)),
+            Some((lib, Span::Range(7..13)))
+        );
+        assert_eq!(map.resolve(&Span::Range(10..16)), None);
+        assert_eq!(map.resolve(&Span::RangeFrom(10..)), None);
+    }
+}
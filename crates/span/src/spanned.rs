@@ -0,0 +1,91 @@
+//! Values with the span they came from.
+
+use std::ops::{Deref, DerefMut};
+
+use crate::Span;
+
+/// A value with the region of source code it came from.
+///
+/// Unlike an input slice, it doesn't borrow the source code.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Spanned<T, Idx = usize> {
+    /// The value.
+    pub value: T,
+    /// Where the value came from.
+    pub span: Span<Idx>,
+}
+
+impl<T, Idx> Spanned<T, Idx> {
+    /// Create a new spanned value.
+    #[inline]
+    pub fn new(value: T, span: Span<Idx>) -> Self {
+        Self { value, span }
+    }
+
+    /// Maps the value and keeps the span.
+    #[inline]
+    pub fn map<U, F>(self, f: F) -> Spanned<U, Idx>
+    where
+        F: FnOnce(T) -> U,
+    {
+        Spanned {
+            value: f(self.value),
+            span: self.span,
+        }
+    }
+
+    /// Borrows the value and keeps a copy of the span.
+    #[inline]
+    pub fn as_ref(&self) -> Spanned<&T, Idx>
+    where
+        Idx: Clone,
+    {
+        Spanned {
+            value: &self.value,
+            span: self.span.clone(),
+        }
+    }
+
+    /// Consumes this wrapper and returns the value.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T, Idx> Deref for Spanned<T, Idx> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T, Idx> DerefMut for Spanned<T, Idx> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spanned() {
+        let mut spanned = Spanned::new(String::from("12"), Span::Range(3..5));
+
+        assert_eq!(spanned.len(), 2);
+
+        spanned.push('3');
+
+        assert_eq!(
+            spanned.as_ref().map(|value| value.parse::<u64>().unwrap()),
+            Spanned::new(123, Span::Range(3..5))
+        );
+        assert_eq!(spanned.into_inner(), "123");
+    }
+}